@@ -4,7 +4,10 @@ use clap::App;
 use yaml_rust::YamlLoader;
 
 use interactive_term::styles;
+use interactive_term::questions;
 use interactive_term::utils;
+use interactive_term::answers;
+use interactive_term::validation;
 use interactive_term::interactive;
 
 fn main() {
@@ -14,8 +17,15 @@ fn main() {
     ).unwrap();
 
     let my_styles = styles::get_styles_from_yaml(&my_yaml_obj[0]);
+    let my_questions = questions::get_interactive_questions_from_yaml(&my_yaml_obj[0]);
+    let my_filter = questions::get_question_filter_from_yaml(&my_yaml_obj[0]).unwrap();
+    let my_arg_infos = questions::get_arg_infos_from_yaml(&my_yaml_obj[0]);
 
-    let clap_matches = App::from_yaml(&my_yaml_obj[0])
+    let clap_app = answers::add_answers_arg(App::from_yaml(&my_yaml_obj[0]), "answers");
+    let mut my_validators = validation::get_validators_from_yaml(&my_yaml_obj[0]).unwrap();
+    my_validators.extend(validation::get_validators_from_app(&clap_app));
+
+    let clap_matches = clap_app
         .get_matches()
         .clone();
 
@@ -23,16 +33,54 @@ fn main() {
         &clap_matches,
         &my_styles,
     );
-
-    match interactive::interact(&mut my_list_items) {
-        Err(e) => panic!("FAILED TO GET INTERACTIVE ANSWERS: {:?}", e),
-        _ => (),
-    };
-
-    let new_matches = utils::replace_matches_from_list_items(
+    utils::add_flag_list_items(
         &clap_matches,
-        &my_list_items,
+        &mut my_list_items,
+        &my_arg_infos,
+        &my_styles,
     );
+    utils::apply_interactive_questions(
+        &clap_matches,
+        &mut my_list_items,
+        &my_questions,
+        &my_styles,
+    );
+    utils::filter_list_items(&mut my_list_items, &my_filter);
+    utils::apply_arg_infos(&mut my_list_items, &my_arg_infos);
+    utils::apply_validators(&mut my_list_items, &my_validators);
+
+    // with --answers <file>, nothing is asked
+    let (new_matches, answers) = match answers::replay_answers(&clap_matches, &mut my_list_items, "answers") {
+        Err(e) => panic!("FAILED TO REPLAY THE ANSWERS: {:?}", e),
+        Ok(Some(replayed)) => replayed,
+        Ok(None) => {
+            let my_options = interactive::InteractOptions {
+                keymap: interactive::get_keymap_from_yaml(&my_yaml_obj[0]).unwrap(),
+                mode: interactive::get_screen_mode_from_yaml(&my_yaml_obj[0]),
+                fallback: interactive::get_fallback_from_yaml(&my_yaml_obj[0]),
+                ..interactive::InteractOptions::default()
+            };
+            let interact_result = interactive::interact_with_options(&mut my_list_items, my_options);
+            match interact_result {
+                Err(e) => panic!("FAILED TO GET INTERACTIVE ANSWERS: {:?}", e),
+                Ok(interactive::InteractResult::Cancelled) => {
+                    println!("cancelled");
+                    return;
+                },
+                Ok(interactive::InteractResult::Submitted(changed)) => {
+                    for index in changed {
+                        println!("changed: {}", my_list_items[index].question);
+                    }
+                },
+            }
+
+            utils::replace_matches_and_get_answers(
+                &clap_matches,
+                &my_list_items,
+            )
+        },
+    };
 
     println!("matches: {:?}", new_matches);
+    println!("interactive answers: {:?}", answers);
 }
\ No newline at end of file
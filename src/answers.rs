@@ -0,0 +1,442 @@
+use clap::{App, Arg, ArgMatches};
+use std::collections::{BTreeMap, HashMap};
+use std::{fs, io, path::Path};
+use yaml_rust::{Yaml, YamlLoader};
+
+use super::utils::{ListItem, replace_matches_and_get_answers};
+
+// the formats an answers file can be saved in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnswersFormat {
+  Yaml,
+  Json,
+  Toml,
+}
+
+// the format of an answers file, from its extension
+pub fn get_answers_format_from_path(path: &Path) -> Option<AnswersFormat> {
+  match path.extension()?.to_str()? {
+    "yml" | "yaml" => Some(AnswersFormat::Yaml),
+    "json" => Some(AnswersFormat::Json),
+    "toml" => Some(AnswersFormat::Toml),
+    _ => None,
+  }
+}
+
+// an answer in an answers file, the same in every format.
+// flags are booleans, or numbers for flags that can occur multiple
+// times, and args that take multiple values are lists.
+#[derive(Debug, Clone, PartialEq)]
+enum FileAnswer {
+  Text(String),
+  Values(Vec<String>),
+  Flag(bool),
+  Count(u64),
+}
+
+impl FileAnswer {
+  fn from_list_item(listitem: &ListItem) -> FileAnswer {
+    if listitem.info.is_flag && listitem.info.multiple {
+      FileAnswer::Count(listitem.get_flag_count())
+    } else if listitem.info.is_flag {
+      FileAnswer::Flag(listitem.get_flag_count() > 0)
+    } else if listitem.is_multiple() {
+      FileAnswer::Values(listitem.values.clone())
+    } else {
+      FileAnswer::Text(listitem.answer.clone())
+    }
+  }
+
+  // the text of an answer that is not a list, eg: a value of a list
+  fn into_text(self) -> Option<String> {
+    match self {
+      FileAnswer::Text(text) => Some(text),
+      FileAnswer::Flag(flag) => Some(flag.to_string()),
+      FileAnswer::Count(count) => Some(count.to_string()),
+      FileAnswer::Values(_) => None,
+    }
+  }
+
+  // a list of the answers of from_value, which are not lists
+  fn from_values<T>(values: &[T], from_value: fn(&T) -> Option<FileAnswer>) -> Option<FileAnswer> {
+    let values: Option<Vec<String>> = values.iter()
+      .map(|value| from_value(value).and_then(FileAnswer::into_text))
+      .collect();
+    values.map(FileAnswer::Values)
+  }
+
+  fn from_yaml(answer: &Yaml) -> Option<FileAnswer> {
+    match answer {
+      Yaml::String(s) | Yaml::Real(s) => Some(FileAnswer::Text(s.clone())),
+      Yaml::Integer(i) if *i >= 0 => Some(FileAnswer::Count(*i as u64)),
+      Yaml::Integer(i) => Some(FileAnswer::Text(i.to_string())),
+      Yaml::Boolean(b) => Some(FileAnswer::Flag(*b)),
+      Yaml::Null => Some(FileAnswer::Text("".to_string())),
+      Yaml::Array(values) => FileAnswer::from_values(values, FileAnswer::from_yaml),
+      _ => None,
+    }
+  }
+
+  fn from_json(answer: &serde_json::Value) -> Option<FileAnswer> {
+    match answer {
+      serde_json::Value::String(s) => Some(FileAnswer::Text(s.clone())),
+      serde_json::Value::Number(n) => match n.as_u64() {
+        Some(count) => Some(FileAnswer::Count(count)),
+        None => Some(FileAnswer::Text(n.to_string())),
+      },
+      serde_json::Value::Bool(b) => Some(FileAnswer::Flag(*b)),
+      serde_json::Value::Null => Some(FileAnswer::Text("".to_string())),
+      serde_json::Value::Array(values) => FileAnswer::from_values(values, FileAnswer::from_json),
+      serde_json::Value::Object(_) => None,
+    }
+  }
+
+  fn from_toml(answer: &toml::Value) -> Option<FileAnswer> {
+    match answer {
+      toml::Value::String(s) => Some(FileAnswer::Text(s.clone())),
+      toml::Value::Integer(i) if *i >= 0 => Some(FileAnswer::Count(*i as u64)),
+      toml::Value::Boolean(b) => Some(FileAnswer::Flag(*b)),
+      toml::Value::Array(values) => FileAnswer::from_values(values, FileAnswer::from_toml),
+      toml::Value::Table(_) => None,
+      answer => Some(FileAnswer::Text(answer.to_string())),
+    }
+  }
+
+  // what follows the question in a yaml map. strings are always
+  // quoted, so an answer like 0x10 or 1_000 is not read as a number.
+  fn to_yaml(&self) -> String {
+    let quote = |text: &str| serde_json::Value::from(text).to_string();
+    match self {
+      FileAnswer::Text(text) => format!(": {}", quote(text)),
+      FileAnswer::Values(values) if values.is_empty() => ": []".to_string(),
+      FileAnswer::Values(values) => values.iter()
+        .map(|value| format!("\n  - {}", quote(value)))
+        .fold(":".to_string(), |out, value| out + &value),
+      FileAnswer::Flag(flag) => format!(": {}", flag),
+      FileAnswer::Count(count) => format!(": {}", count),
+    }
+  }
+
+  fn to_json(&self) -> serde_json::Value {
+    match self {
+      FileAnswer::Text(text) => serde_json::Value::from(text.clone()),
+      FileAnswer::Values(values) => serde_json::Value::from(values.clone()),
+      FileAnswer::Flag(flag) => serde_json::Value::from(*flag),
+      FileAnswer::Count(count) => serde_json::Value::from(*count),
+    }
+  }
+
+  fn to_toml(&self) -> toml::Value {
+    match self {
+      FileAnswer::Text(text) => toml::Value::String(text.clone()),
+      FileAnswer::Values(values) => {
+        toml::Value::Array(values.iter().map(|v| toml::Value::String(v.clone())).collect())
+      },
+      FileAnswer::Flag(flag) => toml::Value::Boolean(*flag),
+      FileAnswer::Count(count) => toml::Value::Integer(*count as i64),
+    }
+  }
+}
+
+// the answers of the list, as a map from the questions to the answers
+pub fn get_answers_string(list: &[ListItem], format: AnswersFormat) -> Result<String, String> {
+  let answers: BTreeMap<&str, FileAnswer> = list.iter()
+    .map(|listitem| (listitem.question.as_str(), FileAnswer::from_list_item(listitem)))
+    .collect();
+
+  match format {
+    AnswersFormat::Yaml => {
+      let mut out = "---\n".to_string();
+      for (question, answer) in answers.iter() {
+        let question = serde_json::Value::from(*question).to_string();
+        out.push_str(&format!("{}{}\n", question, answer.to_yaml()));
+      }
+      Ok(out)
+    },
+    AnswersFormat::Json => {
+      let map = answers.iter()
+        .map(|(question, answer)| (question.to_string(), answer.to_json()))
+        .collect();
+      serde_json::to_string_pretty(&serde_json::Value::Object(map))
+        .map(|out| out + "\n")
+        .map_err(|e| format!("failed to write the answers: {}", e))
+    },
+    AnswersFormat::Toml => {
+      let table = answers.iter()
+        .map(|(question, answer)| (question.to_string(), answer.to_toml()))
+        .collect();
+      toml::to_string(&toml::Value::Table(table))
+        .map_err(|e| format!("failed to write the answers: {}", e))
+    },
+  }
+}
+
+fn parse_answers_string(text: &str, format: AnswersFormat) -> Result<Vec<(String, FileAnswer)>, String> {
+  let not_a_map = || "the answers must be a map".to_string();
+  let answers: Vec<(String, Option<FileAnswer>)> = match format {
+    AnswersFormat::Yaml => {
+      let docs = YamlLoader::load_from_str(text)
+        .map_err(|e| format!("failed to read the answers: {}", e))?;
+      let hash = match docs.first() {
+        Some(Yaml::Hash(hash)) => hash,
+        None | Some(Yaml::Null) => return Ok(vec![]),
+        Some(_) => return Err(not_a_map()),
+      };
+      let mut answers = vec![];
+      for (question, answer) in hash.iter() {
+        let question = FileAnswer::from_yaml(question)
+          .and_then(FileAnswer::into_text)
+          .ok_or("the questions must be strings")?;
+        answers.push((question, FileAnswer::from_yaml(answer)));
+      }
+      answers
+    },
+    AnswersFormat::Json => match serde_json::from_str(text) {
+      Ok(serde_json::Value::Object(map)) => map.iter()
+        .map(|(question, answer)| (question.clone(), FileAnswer::from_json(answer)))
+        .collect(),
+      Ok(_) => return Err(not_a_map()),
+      Err(e) => return Err(format!("failed to read the answers: {}", e)),
+    },
+    AnswersFormat::Toml => match text.parse::<toml::Value>() {
+      Ok(toml::Value::Table(table)) => table.iter()
+        .map(|(question, answer)| (question.clone(), FileAnswer::from_toml(answer)))
+        .collect(),
+      Ok(_) => return Err(not_a_map()),
+      Err(e) => return Err(format!("failed to read the answers: {}", e)),
+    },
+  };
+
+  answers.into_iter()
+    .map(|(question, answer)| match answer {
+      Some(answer) => Ok((question, answer)),
+      None => Err(format!("invalid answer for {}", question)),
+    })
+    .collect()
+}
+
+// sets the answers of the list items from the answers of
+// get_answers_string. the answers to questions that are not
+// in the list are ignored. returns the questions that were answered.
+pub fn apply_answers_string(
+  list: &mut [ListItem],
+  text: &str,
+  format: AnswersFormat,
+) -> Result<Vec<String>, String> {
+  let mut answered = vec![];
+  for (question, answer) in parse_answers_string(text, format)? {
+    let listitem = match list.iter_mut().find(|item| item.question == question) {
+      Some(listitem) => listitem,
+      None => continue,
+    };
+
+    match answer {
+      FileAnswer::Flag(flag) if listitem.info.is_flag => listitem.set_flag_count(flag as u64),
+      FileAnswer::Count(count) if listitem.info.is_flag => listitem.set_flag_count(count),
+      FileAnswer::Text(text) if listitem.info.is_flag => {
+        listitem.answer = text;
+        let count = listitem.get_flag_count();
+        listitem.set_flag_count(count);
+      },
+      FileAnswer::Values(values) if listitem.info.multiple || values.len() > 1 => {
+        listitem.values = values;
+        listitem.value_index = 0;
+        listitem.move_edit_cursor_to_end();
+        listitem.validate();
+        listitem.update_output();
+      },
+      FileAnswer::Values(values) => {
+        listitem.set_answer(values.first().map(String::as_str).unwrap_or(""));
+      },
+      answer => {
+        let text = answer.into_text().unwrap_or_default();
+        listitem.set_answer(&text);
+      },
+    }
+    answered.push(question);
+  }
+  Ok(answered)
+}
+
+fn get_answers_format_or_error(path: &Path) -> io::Result<AnswersFormat> {
+  get_answers_format_from_path(path).ok_or_else(|| io::Error::new(
+    io::ErrorKind::InvalidInput,
+    format!("{} is not a .yml, .yaml, .json or .toml file", path.display()),
+  ))
+}
+
+// saves the answers to a file, in the format of its extension
+pub fn save_answers_file(list: &[ListItem], path: &Path) -> io::Result<()> {
+  let format = get_answers_format_or_error(path)?;
+  let text = get_answers_string(list, format)
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+  fs::write(path, text)
+}
+
+// sets the answers of the list items from a file
+// of save_answers_file, before interact is called
+pub fn load_answers_file(list: &mut [ListItem], path: &Path) -> io::Result<Vec<String>> {
+  let format = get_answers_format_or_error(path)?;
+  let text = fs::read_to_string(path)?;
+  apply_answers_string(list, &text, format)
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))
+}
+
+// adds --<name> <file>, to run without interact using the answers
+// of save_answers_file. the name must not be the name of another arg.
+pub fn add_answers_arg<'a, 'b>(app: App<'a, 'b>, name: &'a str) -> App<'a, 'b> {
+  app.arg(Arg::with_name(name)
+    .long(name)
+    .takes_value(true)
+    .value_name("FILE")
+    .help("Use the answers of this .yml, .json or .toml file instead of asking"))
+}
+
+// like load_answers_file, but fails if a question has no answer,
+// and is not in the file either, or if an answer is invalid
+pub fn replay_answers_file(list: &mut [ListItem], path: &Path) -> io::Result<()> {
+  let answered = load_answers_file(list, path)?;
+
+  let missing: Vec<&str> = list.iter()
+    .filter(|item| !item.info.is_flag && !answered.contains(&item.question))
+    .filter(|item| item.answer.is_empty() && item.values.iter().all(|v| v.is_empty()))
+    .map(|item| item.question.as_str())
+    .collect();
+  if !missing.is_empty() {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("{} has no answers for: {}", path.display(), missing.join(", ")),
+    ));
+  }
+
+  for item in list.iter_mut() {
+    item.validate();
+    item.update_output();
+  }
+  let invalid: Vec<String> = list.iter()
+    .filter_map(|item| item.error.as_ref().map(|e| format!("{} ({})", item.question, e)))
+    .collect();
+  if !invalid.is_empty() {
+    return Err(io::Error::new(
+      io::ErrorKind::InvalidData,
+      format!("{} has invalid answers for: {}", path.display(), invalid.join(", ")),
+    ));
+  }
+
+  Ok(())
+}
+
+// the headless version of interact and replace_matches_and_get_answers.
+// if the matches have the answers arg, the answers of the file are
+// used, and the new matches are returned. if they do not, it
+// returns None, and the answers should be asked with interact.
+// name is the name given to add_answers_arg.
+pub fn replay_answers<'b>(
+  matches: &ArgMatches<'b>,
+  list: &'b mut [ListItem],
+  name: &str,
+) -> io::Result<Option<(ArgMatches<'b>, HashMap<String, String>)>> {
+  let path = match matches.value_of_os(name) {
+    Some(path) => Path::new(path).to_path_buf(),
+    None => return Ok(None),
+  };
+
+  // the arg of the answers file is not a question, it keeps
+  // the path even if the file has an answer with its name
+  let answers_item = list.iter().find(|item| item.question == name).cloned();
+  replay_answers_file(list, &path)?;
+  if let Some(answers_item) = answers_item {
+    if let Some(item) = list.iter_mut().find(|item| item.question == name) {
+      *item = answers_item;
+    }
+  }
+
+  let list: &'b [ListItem] = list;
+  Ok(Some(replace_matches_and_get_answers(matches, list)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::get_styles_from_yaml;
+    use crate::utils::get_list_items_from_matches;
+
+    #[test]
+    fn answers_files_work() {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut list = vec![
+            ListItem::from_styles("name", "a: \"b\"", &styles),
+            ListItem::from_styles("port", "8080", &styles),
+            ListItem::from_styles("debug", "false", &styles),
+            ListItem::from_styles("verbose", "2", &styles),
+            ListItem::from_styles("include", "", &styles),
+        ];
+        list[2].info.is_flag = true;
+        list[3].info.is_flag = true;
+        list[3].info.multiple = true;
+        list[4].info.multiple = true;
+        list[4].values = vec!["x".to_string(), "y z".to_string()];
+
+        // answers that look like numbers stay strings
+        list[0].answer = "1_000".to_string();
+
+        for format in [AnswersFormat::Yaml, AnswersFormat::Json, AnswersFormat::Toml] {
+            let mut changed = list.clone();
+            changed[0].answer = "new name".to_string();
+            changed[1].answer = "0x10".to_string();
+            changed[2].toggle_flag();
+            changed[3].toggle_flag();
+            changed[4].values.extend(["0o7".to_string(), "1_000".to_string()]);
+
+            let text = get_answers_string(&changed, format).unwrap();
+            let mut loaded = list.clone();
+            let answered = apply_answers_string(&mut loaded, &text, format).unwrap();
+            assert_eq!(answered.len(), 5);
+            for (loaded, changed) in loaded.iter().zip(changed.iter()) {
+                assert_eq!(loaded.answer, changed.answer, "{:?}", format);
+                assert_eq!(loaded.values, changed.values, "{:?}", format);
+            }
+        }
+
+        // numbers and booleans are answers too, and other questions are ignored
+        let answered = apply_answers_string(&mut list, "port = 1\ndebug = true\nother = 2", AnswersFormat::Toml).unwrap();
+        assert_eq!(answered, vec!["debug", "port"]);
+        assert_eq!(list[1].answer, "1");
+        assert_eq!(list[2].answer, "true");
+
+        assert!(apply_answers_string(&mut list, "[1, 2]", AnswersFormat::Json).is_err());
+        assert_eq!(get_answers_format_from_path(Path::new("a/b.yml")), Some(AnswersFormat::Yaml));
+        assert_eq!(get_answers_format_from_path(Path::new("b.txt")), None);
+    }
+
+    #[test]
+    fn replay_answers_works() {
+        let app = add_answers_arg(App::new("myapp")
+            .arg(Arg::with_name("a").short("a").takes_value(true).default_value("clap"))
+            .arg(Arg::with_name("b").short("b").takes_value(true).empty_values(true)), "replay");
+        let path = std::env::temp_dir().join(format!("replay_answers_{}.yml", std::process::id()));
+        let path_arg = path.to_str().unwrap();
+        let matches = app.get_matches_from(vec!["myapp", "-b", "", "--replay", path_arg]);
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let list = get_list_items_from_matches(&matches, &styles);
+        assert_eq!(list.len(), 3);
+
+        // b has no answer
+        fs::write(&path, "a: file").unwrap();
+        let mut replayed = list.clone();
+        let error = replay_answers(&matches, &mut replayed, "replay").unwrap_err();
+        assert!(error.to_string().ends_with("has no answers for: b"));
+
+        // the answers arg is not a question
+        fs::write(&path, "a: file\nb: 2\nreplay: other").unwrap();
+        let mut replayed = list.clone();
+        let (new_matches, _) = replay_answers(&matches, &mut replayed, "replay").unwrap().unwrap();
+        assert_eq!(new_matches.value_of("a"), Some("file"));
+        assert_eq!(new_matches.value_of("b"), Some("2"));
+        assert_eq!(new_matches.value_of("replay"), Some(path_arg));
+        fs::remove_file(&path).unwrap();
+
+        let matches = add_answers_arg(App::new("myapp"), "replay").get_matches_from(vec!["myapp"]);
+        assert!(replay_answers(&matches, &mut list.clone(), "replay").unwrap().is_none());
+    }
+}
@@ -0,0 +1,116 @@
+use crossterm::{ClearType, Terminal, TerminalCursor};
+use std::{io, io::IsTerminal, io::Write};
+
+use super::{Events, ScreenMode, TerminalGuard};
+
+// everything interact does with the terminal. the crossterm
+// backend uses the real one, and the memory backend
+// a screen that tests can look at.
+pub trait Backend {
+    // the columns and rows of the screen
+    fn size(&self) -> (u16, u16);
+    fn cursor_pos(&mut self) -> (u16, u16);
+    fn goto(&mut self, x: u16, y: u16) -> io::Result<()>;
+    fn show_cursor(&mut self) -> io::Result<()>;
+    fn hide_cursor(&mut self) -> io::Result<()>;
+    // clears the screen, and moves the cursor to the top left
+    fn clear_all(&mut self) -> io::Result<()>;
+    fn clear_line(&mut self) -> io::Result<()>;
+    // writes text at the cursor. the text can contain
+    // the escape codes of the styles.
+    fn write(&mut self, text: &str) -> io::Result<()>;
+    fn flush(&mut self) -> io::Result<()>;
+    // starts sending the input to the events
+    fn read_input(&mut self, events: &Events);
+    // gets the screen ready for interact, eg: raw mode
+    fn enter(&mut self, mode: ScreenMode) -> io::Result<()>;
+    // puts the screen back like it was before enter
+    fn leave(&mut self) -> io::Result<()>;
+}
+
+// the terminal interact runs in by default
+pub struct CrosstermBackend {
+    terminal: Terminal,
+    cursor: TerminalCursor,
+    guard: Option<TerminalGuard>,
+}
+
+impl Default for CrosstermBackend {
+    fn default() -> CrosstermBackend {
+        CrosstermBackend {
+            terminal: Terminal::new(),
+            cursor: TerminalCursor::new(),
+            guard: None,
+        }
+    }
+}
+
+impl CrosstermBackend {
+    pub fn new() -> CrosstermBackend {
+        CrosstermBackend::default()
+    }
+}
+
+impl Backend for CrosstermBackend {
+    fn size(&self) -> (u16, u16) {
+        self.terminal.terminal_size()
+    }
+
+    // the position is read from the input, so this
+    // has to be called before read_input
+    fn cursor_pos(&mut self) -> (u16, u16) {
+        self.cursor.pos()
+    }
+
+    fn goto(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.cursor.goto(x, y)?;
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.cursor.show()?;
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.cursor.hide()?;
+        Ok(())
+    }
+
+    fn clear_all(&mut self) -> io::Result<()> {
+        self.terminal.clear(ClearType::All)?;
+        Ok(())
+    }
+
+    fn clear_line(&mut self) -> io::Result<()> {
+        self.terminal.clear(ClearType::CurrentLine)?;
+        Ok(())
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        write!(io::stdout(), "{}", text)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+
+    fn read_input(&mut self, events: &Events) {
+        events.read_input();
+    }
+
+    fn enter(&mut self, mode: ScreenMode) -> io::Result<()> {
+        // raw mode needs the input to be a terminal, and the
+        // list is drawn on the output, which can not be piped
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return Err(io::Error::other("the input or the output is not a terminal"));
+        }
+        self.guard = Some(TerminalGuard::new(mode)?);
+        Ok(())
+    }
+
+    fn leave(&mut self) -> io::Result<()> {
+        self.guard = None;
+        Ok(())
+    }
+}
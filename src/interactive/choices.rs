@@ -0,0 +1,79 @@
+use super::super::utils::ListItem;
+
+pub fn has_choices(item: &ListItem) -> bool {
+    !item.info.possible_values.is_empty()
+}
+
+// moves the answer (or the selected value of an arg that takes
+// multiple values) to the next (direction 1) or previous (direction -1)
+// possible value, wrapping around at both ends.
+// an answer that is not a possible value moves to the first one.
+pub fn cycle_choice(item: &mut ListItem, direction: i32) {
+    let values = &item.info.possible_values;
+    if values.is_empty() {
+        return;
+    }
+
+    let len = values.len() as i32;
+    let next_index = match values.iter().position(|v| v == item.get_edit_text()) {
+        Some(index) => (index as i32 + direction).rem_euclid(len),
+        None => 0,
+    };
+
+    let value = values[next_index as usize].clone();
+    *item.get_edit_text_mut() = value;
+    item.update_output();
+}
+
+// sets the answer to the first possible value that starts with
+// the search text, ignoring case. returns false if none do.
+pub fn jump_to_choice(item: &mut ListItem, search: &str) -> bool {
+    let search = search.to_lowercase();
+    let found = item.info.possible_values.iter()
+        .find(|v| v.to_lowercase().starts_with(&search))
+        .cloned();
+
+    match found {
+        Some(value) => {
+            *item.get_edit_text_mut() = value;
+            item.update_output();
+            true
+        },
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::get_styles_from_yaml;
+    use yaml_rust::Yaml;
+
+    #[test]
+    fn cycle_and_jump_work() {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut item = ListItem::from_styles("color", "not a choice", &styles);
+        item.info.possible_values = vec![
+            "red".to_string(),
+            "green".to_string(),
+            "grey".to_string(),
+        ];
+
+        cycle_choice(&mut item, 1);
+        assert_eq!(item.answer, "red");
+        cycle_choice(&mut item, -1);
+        assert_eq!(item.answer, "grey");
+        cycle_choice(&mut item, 1);
+        assert_eq!(item.answer, "red");
+
+        assert!(jump_to_choice(&mut item, "G"));
+        assert_eq!(item.answer, "green");
+        assert!(jump_to_choice(&mut item, "gre"));
+        assert_eq!(item.answer, "green");
+        assert!(jump_to_choice(&mut item, "grey"));
+        assert_eq!(item.answer, "grey");
+        assert!(!jump_to_choice(&mut item, "blue"));
+        assert_eq!(item.answer, "grey");
+        assert!(item.regular_output.contains("grey"));
+    }
+}
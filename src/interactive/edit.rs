@@ -0,0 +1,131 @@
+use super::super::utils::{ListItem, pop_grapheme};
+use super::Action;
+use super::edit_line;
+use super::{has_choices, cycle_choice, jump_to_choice};
+use super::{select_value, add_value, remove_value, move_value};
+
+// applies an edit mode action to the item being edited.
+// choice_search is what was typed so far when editing an
+// item with possible values.
+// returns false if the action does nothing for this item.
+pub fn edit_list_item(
+    item: &mut ListItem,
+    action: &Action,
+    choice_search: &mut String,
+) -> bool {
+    let is_choice = has_choices(item);
+    let is_flag = item.info.is_flag;
+    let is_multiple = item.is_multiple();
+
+    if !is_flag && !is_choice {
+        let mut cursor = item.edit_cursor;
+        if edit_line(item.get_edit_text_mut(), &mut cursor, action) {
+            item.edit_cursor = cursor;
+            item.validate();
+            item.update_output();
+            return true;
+        }
+    }
+
+    match action {
+        Action::InsertChar(c) if c.is_control() => return false,
+        Action::InsertChar(c) => {
+            if is_flag {
+                match c {
+                    ' ' | '+' => item.toggle_flag(),
+                    '-' => {
+                        let count = item.get_flag_count();
+                        item.set_flag_count(count.saturating_sub(1));
+                    },
+                    _ => return false,
+                }
+            } else {
+                // jump to the value that starts with what was typed,
+                // or start a new search from this char
+                choice_search.push(*c);
+                if !jump_to_choice(item, choice_search) {
+                    *choice_search = c.to_string();
+                    jump_to_choice(item, choice_search);
+                }
+            }
+        },
+        Action::Backspace => {
+            if is_flag {
+                let count = item.get_flag_count();
+                item.set_flag_count(count.saturating_sub(1));
+            } else {
+                // with nothing left to search for, the answer stays
+                pop_grapheme(choice_search);
+                if choice_search.is_empty() || !jump_to_choice(item, choice_search) {
+                    return false;
+                }
+            }
+        },
+        Action::MoveUp | Action::MoveDown => {
+            let direction = if *action == Action::MoveUp { -1 } else { 1 };
+            choice_search.clear();
+            // values with choices are selected with Left/Right instead
+            if is_choice {
+                cycle_choice(item, direction);
+            } else if is_multiple {
+                select_value(item, direction);
+            } else {
+                return false;
+            }
+        },
+        Action::CursorLeft | Action::CursorRight if is_multiple && is_choice => {
+            let direction = if *action == Action::CursorLeft { -1 } else { 1 };
+            choice_search.clear();
+            select_value(item, direction);
+        },
+        Action::NewValue if is_multiple => {
+            choice_search.clear();
+            add_value(item);
+        },
+        Action::DeleteValue if is_multiple => {
+            choice_search.clear();
+            remove_value(item);
+        },
+        Action::MoveValueUp if is_multiple => {
+            move_value(item, -1);
+        },
+        Action::MoveValueDown if is_multiple => {
+            move_value(item, 1);
+        },
+        _ => return false,
+    }
+
+    item.move_edit_cursor_to_end();
+    item.validate();
+    item.update_output();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::get_styles_from_yaml;
+    use yaml_rust::Yaml;
+
+    #[test]
+    fn editing_choices_works() {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut item = ListItem::from_styles("color", "blue", &styles);
+        item.info.possible_values = vec!["red".to_string(), "blue".to_string()];
+        let mut search = String::new();
+
+        // nothing was typed, so the answer stays
+        assert!(!edit_list_item(&mut item, &Action::Backspace, &mut search));
+        assert_eq!(item.answer, "blue");
+
+        // values with choices cycle with Up/Down, and are selected with Left/Right
+        item.info.multiple = true;
+        item.values = vec!["red".to_string(), "red".to_string()];
+        assert!(edit_list_item(&mut item, &Action::MoveDown, &mut search));
+        assert_eq!(item.values, vec!["blue", "red"]);
+        assert!(edit_list_item(&mut item, &Action::CursorRight, &mut search));
+        assert!(edit_list_item(&mut item, &Action::MoveUp, &mut search));
+        assert_eq!(item.values, vec!["blue", "blue"]);
+        assert_eq!(item.value_index, 1);
+    }
+}
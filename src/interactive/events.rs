@@ -1,62 +1,275 @@
-use crossterm::{InputEvent, KeyEvent};
-
-pub enum KeyCharPressed {
-  Backspace,
-  Char(char),
-  Exit,
-  None,
-  Quit,
-}
-
-
-pub fn get_key_char(key_event: InputEvent) -> KeyCharPressed {
-  match key_event {
-        InputEvent::Keyboard(k) => {
-            match k {
-                KeyEvent::Char(c) => { return KeyCharPressed::Char(c) },
-                KeyEvent::Backspace => { return KeyCharPressed::Backspace },
-                KeyEvent::Ctrl(c) => match c {
-                  'w' => {
-                    return KeyCharPressed::Exit;
-                  },
-                  'g' => {
-                    return KeyCharPressed::Quit;
-                  }
-                  _ => (),
-                }
-                _ => (),
-            }
+use crossterm::{input, InputEvent, KeyEvent};
+use std::{thread, thread::JoinHandle, time::Duration};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender, Receiver, RecvTimeoutError};
+
+use super::super::utils::ListItem;
+use super::{Keymap, Action};
+
+pub type UpdateItems = Box<dyn FnOnce(&mut [ListItem]) + Send>;
+
+// what the interact loop waits for: input from the terminal,
+// or events the host application sends from other threads
+pub enum InteractEvent {
+    Input(InputEvent),
+    // handled as if a key bound to the action was pressed
+    Action(Action),
+    // sets the answer of the item with this question
+    SetAnswer(String, String),
+    // changes the items, eg: several answers at once
+    Update(UpdateItems),
+    // prints everything again
+    Redraw,
+}
+
+// the channel of the interact loop. events can be
+// sent to it with the senders, from any thread.
+pub struct Events {
+    sender: Sender<InteractEvent>,
+    receiver: Receiver<InteractEvent>,
+    // tells the thread of read_input to stop. every thread gets
+    // its own, so one that was not waited for never starts again.
+    stop: Mutex<Arc<AtomicBool>>,
+    reader: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Default for Events {
+    fn default() -> Events {
+        let (sender, receiver) = channel();
+        Events {
+            sender,
+            receiver,
+            stop: Mutex::new(Arc::new(AtomicBool::new(false))),
+            reader: Mutex::new(None),
         }
-        _ => (),
     }
+}
 
-    return KeyCharPressed::None;
+impl Drop for Events {
+    fn drop(&mut self) {
+        self.stop_input();
+    }
 }
 
-pub fn down_or_up(key_event: InputEvent) -> i32 {
-    match key_event {
-        InputEvent::Keyboard(k) => {
-            match k {
-                KeyEvent::Ctrl(c) => match c {
-                  'w' => {
-                    return 254;
-                  },
-                  'g' => {
-                    return 255;
-                  },
-                  _ => ()
-                }
-                KeyEvent::Up => {
-                    return -1;
-                }
-                KeyEvent::Down => {
-                    return 1;
+impl Events {
+    pub fn new() -> Events {
+        Events::default()
+    }
+
+    pub fn sender(&self) -> Sender<InteractEvent> {
+        self.sender.clone()
+    }
+
+    // starts a thread that sends the terminal input to the channel,
+    // until stop_input is called. it has to be called in raw mode,
+    // and after the cursor position was read.
+    pub fn read_input(&self) {
+        self.stop_input();
+        let stop = Arc::new(AtomicBool::new(false));
+        *self.stop.lock().unwrap() = stop.clone();
+
+        // opened once, the thread checks it after every timeout
+        let tty = open_tty_with_read_timeout();
+        let times_out = tty.is_some();
+
+        let sender = self.sender();
+        let reader = thread::spawn(move || {
+            let mut reader = input().read_sync();
+            while !stop.load(Ordering::SeqCst) {
+                let event = match reader.next() {
+                    Some(event) => event,
+                    // the read timed out, the input could not be parsed,
+                    // or the terminal is gone
+                    None if is_input_closed(tty.as_ref()) => return,
+                    None => continue,
+                };
+                // a key read after stop_input is not for interact
+                if stop.load(Ordering::SeqCst) || sender.send(InteractEvent::Input(event)).is_err() {
+                    return;
                 }
-                _ => ()
             }
+        });
+
+        // a thread whose reads block until a key is pressed is not
+        // waited for. it stops at the next key, and drops it.
+        if times_out {
+            *self.reader.lock().unwrap() = Some(reader);
+        }
+    }
+
+    // stops the thread of read_input, so it does not read
+    // the keys that are typed after interact returns
+    pub fn stop_input(&self) {
+        self.stop.lock().unwrap().store(true, Ordering::SeqCst);
+        if let Some(reader) = self.reader.lock().unwrap().take() {
+            let _ = reader.join();
         }
-        _ => ()
     }
 
-    return 0;
-}
\ No newline at end of file
+    // blocks until there is an event, or until the timeout
+    pub fn next(&self, timeout: Option<Duration>) -> Option<InteractEvent> {
+        match timeout {
+            Some(timeout) => match self.receiver.recv_timeout(timeout) {
+                Ok(event) => Some(event),
+                Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+            },
+            None => self.receiver.recv().ok(),
+        }
+    }
+}
+
+// the terminal, set so that a read returns after a tenth of a
+// second without input. the reader thread can then see that it
+// has to stop. raw mode sets it back to blocking when it is left.
+#[cfg(unix)]
+fn open_tty_with_read_timeout() -> Option<std::fs::File> {
+    use std::os::unix::io::AsRawFd;
+    let tty = std::fs::File::open("/dev/tty").ok()?;
+    let fd = tty.as_raw_fd();
+    unsafe {
+        let mut termios: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut termios) != 0 {
+            return None;
+        }
+        termios.c_cc[libc::VMIN] = 0;
+        termios.c_cc[libc::VTIME] = 1;
+        if libc::tcsetattr(fd, libc::TCSANOW, &termios) != 0 {
+            return None;
+        }
+    }
+    Some(tty)
+}
+
+// elsewhere the read blocks until a key is pressed
+#[cfg(not(unix))]
+fn open_tty_with_read_timeout() -> Option<std::fs::File> {
+    None
+}
+
+// whether the terminal was closed, eg: its window,
+// or could not be opened at all
+#[cfg(unix)]
+fn is_input_closed(tty: Option<&std::fs::File>) -> bool {
+    use std::os::unix::io::AsRawFd;
+    let tty = match tty {
+        Some(tty) => tty,
+        None => return true,
+    };
+    let mut fd = libc::pollfd {
+        fd: tty.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let ready = unsafe { libc::poll(&mut fd, 1, 0) };
+    ready < 0 || fd.revents & (libc::POLLHUP | libc::POLLERR | libc::POLLNVAL) != 0
+}
+
+#[cfg(not(unix))]
+fn is_input_closed(_tty: Option<&std::fs::File>) -> bool {
+    false
+}
+
+// the only place where input events are turned into actions.
+// pending_keys are the keys typed so far of a sequence like gg,
+// it is kept between calls.
+pub fn get_action(
+  event: InputEvent,
+  keymap: &Keymap,
+  is_editing: bool,
+  pending_keys: &mut Vec<KeyEvent>,
+) -> Action {
+  if let InputEvent::Keyboard(k) = event {
+      if is_editing {
+        pending_keys.clear();
+        // in edit mode, typed chars are always part of the answer.
+        // enter and tab are chars too, they are looked up in the keymap.
+        if let KeyEvent::Char(c) = k {
+          if !c.is_control() {
+            return Action::InsertChar(c);
+          }
+        }
+      }
+
+      pending_keys.push(k.clone());
+      if keymap.is_prefix(pending_keys, is_editing) {
+        // wait for the rest of the sequence
+        return Action::None;
+      }
+      if let Some(action) = keymap.get_action(pending_keys, is_editing) {
+        pending_keys.clear();
+        return action;
+      }
+
+      // the sequence does not match, but the last key
+      // might be a key or the start of a sequence itself
+      let restart = pending_keys.len() > 1;
+      pending_keys.clear();
+      if restart {
+        return get_action(InputEvent::Keyboard(k), keymap, is_editing, pending_keys);
+      }
+  }
+
+  Action::None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_action_works() {
+        let keymap = Keymap::default();
+        let key = |k| InputEvent::Keyboard(k);
+        let mut pending = vec![];
+
+        assert_eq!(get_action(key(KeyEvent::Up), &keymap, false, &mut pending), Action::MoveUp);
+        assert_eq!(get_action(key(KeyEvent::Ctrl('g')), &keymap, true, &mut pending), Action::Submit);
+        assert_eq!(get_action(key(KeyEvent::Char('?')), &keymap, false, &mut pending), Action::ToggleHelp);
+        assert_eq!(get_action(key(KeyEvent::Char('?')), &keymap, true, &mut pending), Action::InsertChar('?'));
+        assert_eq!(get_action(key(KeyEvent::Ctrl('w')), &keymap, false, &mut pending), Action::Edit);
+        assert_eq!(get_action(key(KeyEvent::Ctrl('w')), &keymap, true, &mut pending), Action::DeleteWordBack);
+        assert_eq!(get_action(key(KeyEvent::Char('\n')), &keymap, true, &mut pending), Action::StopEditing);
+        assert_eq!(get_action(key(KeyEvent::Char('\t')), &keymap, true, &mut pending), Action::None);
+        assert_eq!(get_action(key(KeyEvent::Char('x')), &keymap, false, &mut pending), Action::None);
+        assert_eq!(get_action(InputEvent::Unknown, &keymap, false, &mut pending), Action::None);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn events_work() {
+        let events = Events::new();
+        let sender = events.sender();
+        assert!(events.next(Some(Duration::from_millis(1))).is_none());
+
+        thread::spawn(move || {
+            sender.send(InteractEvent::SetAnswer("a".to_string(), "b".to_string())).unwrap();
+        }).join().unwrap();
+        match events.next(None) {
+            Some(InteractEvent::SetAnswer(question, answer)) => {
+                assert_eq!((question.as_str(), answer.as_str()), ("a", "b"));
+            },
+            _ => panic!("expected the SetAnswer event"),
+        }
+    }
+
+    #[test]
+    fn get_action_sequences_work() {
+        let keymap = Keymap::vim();
+        let mut pending = vec![];
+        let mut press = |c| get_action(InputEvent::Keyboard(KeyEvent::Char(c)), &keymap, false, &mut pending);
+
+        assert_eq!(press('g'), Action::None);
+        assert_eq!(press('g'), Action::Top);
+        assert_eq!(press(':'), Action::None);
+        assert_eq!(press('w'), Action::None);
+        assert_eq!(press('q'), Action::Submit);
+        // a key that breaks a sequence is used on its own
+        assert_eq!(press('g'), Action::None);
+        assert_eq!(press('j'), Action::MoveDown);
+        assert_eq!(press('c'), Action::None);
+        assert_eq!(press('c'), Action::ChangeAnswer);
+        assert_eq!(press('d'), Action::None);
+        assert_eq!(press('d'), Action::ClearAnswer);
+    }
+}
@@ -0,0 +1,135 @@
+use crossterm::{AlternateScreen, RawScreen, TerminalCursor};
+use std::{io, io::Write, mem, panic, thread};
+use std::sync::{Arc, Mutex};
+
+use super::ScreenMode;
+
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo) + Sync + Send + 'static>;
+
+// what has to be put back when interact is done
+struct Screens {
+    alternate: Option<AlternateScreen>,
+    raw: RawScreen,
+}
+
+// puts the terminal in the state interact needs: raw mode, and the
+// alternate screen in full screen mode. everything is put back when
+// the guard is dropped, so on every return, error and panic.
+pub struct TerminalGuard {
+    // shared with the panic hook, whichever runs first restores them
+    screens: Arc<Mutex<Option<Screens>>>,
+    previous_hook: Option<Arc<PanicHook>>,
+}
+
+impl TerminalGuard {
+    pub fn new(mode: ScreenMode) -> io::Result<TerminalGuard> {
+        let raw = RawScreen::into_raw_mode()?;
+        let alternate = match mode {
+            ScreenMode::FullScreen => Some(AlternateScreen::to_alternate(false)?),
+            ScreenMode::Inline => None,
+        };
+
+        // the panic message is printed before the guard is dropped,
+        // so it would be printed on the alternate screen and lost.
+        // the terminal is restored first, then the message is printed.
+        let screens = Arc::new(Mutex::new(Some(Screens { alternate, raw })));
+        let previous_hook = set_restore_hook(screens.clone(), restore_terminal);
+
+        Ok(TerminalGuard {
+            screens,
+            previous_hook: Some(previous_hook),
+        })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if let Some(screens) = take_state(&self.screens) {
+            restore_terminal(screens);
+        }
+
+        // the hook can not be changed while panicking. it is left
+        // in place then, and it does nothing since the state is gone.
+        if let Some(previous_hook) = self.previous_hook.take() {
+            if !thread::panicking() {
+                let _ = panic::take_hook();
+                panic::set_hook(Box::new(move |info| previous_hook(info)));
+            }
+        }
+    }
+}
+
+// a poisoned lock still has the state, the terminal has to be restored anyway
+fn take_state<T>(state: &Mutex<Option<T>>) -> Option<T> {
+    match state.lock() {
+        Ok(mut state) => state.take(),
+        Err(poisoned) => poisoned.into_inner().take(),
+    }
+}
+
+// sets a panic hook that restores the state, if it was not
+// restored yet, before calling the hook that was set before.
+// returns the hook that was set before.
+fn set_restore_hook<T: Send + 'static>(
+    state: Arc<Mutex<Option<T>>>,
+    restore: fn(T),
+) -> Arc<PanicHook> {
+    let previous_hook = Arc::new(panic::take_hook());
+    let hook = previous_hook.clone();
+    panic::set_hook(Box::new(move |info| {
+        if let Some(state) = take_state(&state) {
+            restore(state);
+        }
+        hook(info);
+    }));
+    previous_hook
+}
+
+// back to the main screen, then out of raw mode. the errors are
+// ignored, the terminal is restored as far as it can be, even
+// while panicking.
+fn restore_terminal(screens: Screens) {
+    let Screens { alternate, mut raw } = screens;
+    let _ = TerminalCursor::new().show();
+    if let Some(alternate) = alternate {
+        // dropping it would unwrap the error of to_main
+        let _ = alternate.to_main();
+        mem::forget(alternate);
+    }
+    raw.disable_drop();
+    let _ = RawScreen::disable_raw_mode();
+    let _ = io::stdout().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static RESTORED: AtomicUsize = AtomicUsize::new(0);
+
+    fn restore(count: usize) {
+        RESTORED.fetch_add(count, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn restore_hook_works() {
+        let state = Arc::new(Mutex::new(Some(1)));
+        let previous_hook = set_restore_hook(state.clone(), restore);
+
+        // the state is restored once, then the hook only calls the previous one
+        assert!(panic::catch_unwind(|| panic!("restores")).is_err());
+        assert!(panic::catch_unwind(|| panic!("does nothing")).is_err());
+        assert_eq!(RESTORED.load(Ordering::SeqCst), 1);
+        assert!(state.lock().unwrap().is_none());
+
+        // a state that was restored before the panic is not restored again
+        *state.lock().unwrap() = Some(2);
+        assert_eq!(take_state(&state), Some(2));
+        assert!(panic::catch_unwind(|| panic!("does nothing")).is_err());
+        assert_eq!(RESTORED.load(Ordering::SeqCst), 1);
+
+        let _ = panic::take_hook();
+        panic::set_hook(Box::new(move |info| previous_hook(info)));
+    }
+}
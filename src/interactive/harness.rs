@@ -0,0 +1,173 @@
+use crossterm::KeyEvent;
+use std::io;
+
+use super::super::utils::ListItem;
+use super::{interact_with_options, Fallback, InteractOptions, InteractResult, Keymap, MemoryBackend};
+
+// what a scripted run of interact did
+#[derive(Debug, Clone)]
+pub struct ScriptResult {
+    pub result: InteractResult,
+    // the items after interact returned
+    pub items: Vec<ListItem>,
+    // the text of the screen after every frame, a line per row
+    pub frames: Vec<String>,
+}
+
+impl ScriptResult {
+    pub fn get_last_frame(&self) -> &str {
+        self.frames.last().map(|frame| frame.as_str()).unwrap_or("")
+    }
+}
+
+// runs interact on a copy of the items, on a screen of width
+// by height cells, as if the keys were typed. if the keys do
+// not submit, it is cancelled when they run out. eg:
+//
+// let script = run_script(&items, &Keymap::default(), &[KeyEvent::Down, KeyEvent::Ctrl('g')], 80, 24)?;
+// assert_eq!(script.result, InteractResult::Submitted(vec![]));
+// assert_eq!(script.frames[1].lines().next(), Some("name: value"));
+pub fn run_script(
+    items: &[ListItem],
+    keymap: &Keymap,
+    keys: &[KeyEvent],
+    width: u16,
+    height: u16,
+) -> io::Result<ScriptResult> {
+    let mut items = items.to_vec();
+    let mut backend = MemoryBackend::new(width, height);
+    backend.add_keys(keys);
+
+    let result = interact_with_options(&mut items, InteractOptions {
+        keymap: keymap.clone(),
+        fallback: Fallback::Error,
+        backend: Some(&mut backend),
+        ..InteractOptions::default()
+    })?;
+
+    Ok(ScriptResult {
+        result,
+        items,
+        frames: backend.get_frames().to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::truncate_to_width;
+    use crate::styles::get_styles_from_yaml;
+    use yaml_rust::Yaml;
+
+    fn get_items(count: usize) -> Vec<ListItem> {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        (0..count)
+            .map(|i| ListItem::from_styles(&format!("q{}", i), &format!("a{}", i), &styles))
+            .collect()
+    }
+
+    #[test]
+    fn run_script_scrolls() {
+        let items = get_items(20);
+        let keys = vec![KeyEvent::Down; 12];
+        let script = run_script(&items, &Keymap::default(), &keys, 40, 12).unwrap();
+
+        assert_eq!(script.result, InteractResult::Cancelled);
+        // the first frame, and one for every key
+        assert_eq!(script.frames.len(), 13);
+        assert!(script.frames[0].starts_with("q0: a0\nq1: a1\n"));
+
+        let last_frame: Vec<&str> = script.get_last_frame().lines().collect();
+        assert_eq!(last_frame[0], "q3: a3");
+        assert_eq!(last_frame[9], "q12: a12");
+    }
+
+    #[test]
+    fn run_script_edits() {
+        let items = get_items(3);
+        let keys = vec![
+            KeyEvent::Down,
+            KeyEvent::Ctrl('w'),
+            KeyEvent::Ctrl('u'),
+            KeyEvent::Char('n'),
+            KeyEvent::Char('e'),
+            KeyEvent::Char('w'),
+            KeyEvent::Ctrl('g'),
+        ];
+        let script = run_script(&items, &Keymap::default(), &keys, 40, 12).unwrap();
+
+        assert_eq!(script.result, InteractResult::Submitted(vec![1]));
+        assert_eq!(script.items[1].answer, "new");
+        assert!(script.get_last_frame().starts_with("q0: a0\nq1: new\nq2: a2"));
+        // the answer given to run_script is not changed
+        assert_eq!(items[1].answer, "a1");
+    }
+
+    #[test]
+    fn run_script_stops_editing_on_enter() {
+        let items = get_items(1);
+        let keys = vec![
+            KeyEvent::Ctrl('w'),
+            KeyEvent::Char('x'),
+            KeyEvent::Char('\t'),
+            KeyEvent::Char('\n'),
+            KeyEvent::Ctrl('g'),
+        ];
+        let script = run_script(&items, &Keymap::default(), &keys, 40, 12).unwrap();
+
+        assert_eq!(script.result, InteractResult::Submitted(vec![0]));
+        assert_eq!(script.items[0].answer, "a0x");
+    }
+
+    #[test]
+    fn run_script_clears_with_vim_keys() {
+        let mut items = get_items(2);
+        items[1].info.multiple = true;
+        items[1].values = vec!["x".to_string(), "y".to_string()];
+        let keys: Vec<KeyEvent> = "ddjdd:wq".chars().map(KeyEvent::Char).collect();
+        let script = run_script(&items, &Keymap::vim(), &keys, 40, 12).unwrap();
+
+        assert_eq!(script.result, InteractResult::Submitted(vec![0, 1]));
+        assert_eq!(script.items[0].answer, "");
+        assert_eq!(script.items[1].values, vec!["y"]);
+    }
+
+    #[test]
+    fn run_script_pages_through_wrapped_items() {
+        let mut items = get_items(20);
+        for item in items[3..=10].iter_mut() {
+            item.set_answer(&"a".repeat(230));
+        }
+        let script = run_script(&items, &Keymap::default(), &[KeyEvent::PageDown], 80, 24).unwrap();
+
+        // the highlighted item is on the screen
+        let highlighted = items[11].get_visible_text(true);
+        assert!(script.get_last_frame().lines().any(|line| line == highlighted));
+    }
+
+    #[test]
+    fn run_script_keeps_the_footer_in_its_row() {
+        let mut items = get_items(2);
+        items[1].info.multiple = true;
+        items[1].info.possible_values = vec!["x".to_string(), "y".to_string()];
+        items[1].values = vec!["x".to_string(), "y".to_string()];
+        let keys = vec![KeyEvent::Down, KeyEvent::Ctrl('w'), KeyEvent::Esc];
+
+        for &width in [80, 20].iter() {
+            let script = run_script(&items, &Keymap::default(), &keys, width, 24).unwrap();
+            let footer = truncate_to_width("CTRL-g to submit. CTRL-c to cancel. CTRL-w to edit. ? to toggle help", width);
+            let edit_footer = truncate_to_width("CTRL-g to submit. CTRL-c to cancel. Esc to stop editing", width);
+
+            // nothing wraps into the rows below, or scrolls the list up
+            for frame in script.frames.iter() {
+                let lines: Vec<&str> = frame.split('\n').collect();
+                assert_eq!(lines.len(), 24);
+                assert!(lines[0].starts_with("q0: a0"));
+                assert!(lines[22] == footer || lines[22] == edit_footer);
+                assert_eq!(lines[23], "");
+            }
+            assert_eq!(script.frames[2].lines().nth(22), Some(edit_footer.as_str()));
+            assert_eq!(script.get_last_frame().lines().nth(22), Some(footer.as_str()));
+        }
+    }
+}
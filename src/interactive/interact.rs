@@ -1,183 +1,797 @@
-use crossterm::{
-    TerminalCursor,
-    input,
-    InputEvent,
-    KeyEvent,
-    RawScreen,
-    ClearType,
-    Color,
-    Crossterm,
-};
-use std::{thread, io, time::Duration};
-
-use super::super::utils::ListItem;
-use super::print_list;
-use super::get_key_char;
-use super::down_or_up;
-use super::KeyCharPressed;
-
-
-pub fn interact(out_vec: &mut Vec<ListItem>) -> io::Result<()> {
+use crossterm::KeyEvent;
+use std::{io, time::Duration};
+use yaml_rust::Yaml;
+
+use super::super::utils::{ListItem, all_list_items_valid};
+use super::{Frame, Renderer, add_list_to_frame, add_help_to_frame};
+use super::{get_wrapped_height, truncate_to_width};
+use super::{get_action, Events, InteractEvent};
+use super::edit_list_item;
+use super::{has_choices, remove_value};
+use super::{Keymap, Action};
+use super::{Backend, CrosstermBackend};
+use super::{Fallback, prompt_list_items};
+
+
+// how long to wait for an event before checking
+// if the terminal was resized
+const RESIZE_CHECK_MILLIS: u64 = 200;
+
+// the rows of the help pane, with its separator
+const HELP_HEIGHT: u16 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScreenMode {
+    // the list takes the whole screen
+    FullScreen,
+    // the list is printed under the cursor, in as few rows as it
+    // needs, and the answers are left in the scrollback after it
+    Inline,
+}
+
+// interactive_inline: true
+pub fn get_screen_mode_from_yaml(yaml_obj: &Yaml) -> ScreenMode {
+    match yaml_obj["interactive_inline"].as_bool() {
+        Some(true) => ScreenMode::Inline,
+        _ => ScreenMode::FullScreen,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InteractResult {
+    // the user submitted their answers. contains the
+    // indices of the items whose answers were changed.
+    Submitted(Vec<usize>),
+    // the user cancelled, and the answers were restored
+    Cancelled,
+}
+
+// the indices of the items whose answer (or values) differ
+pub fn get_changed_items(original: &[ListItem], current: &[ListItem]) -> Vec<usize> {
+    original.iter().zip(current.iter()).enumerate()
+        .filter(|(_, (o, c))| o.answer != c.answer || o.values != c.values)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// where the list is scrolled to, and what is shown around it
+struct ListState {
+    cursor_position: i32,
+    cursor_offset: usize,
+    editing: bool,
+    // what was typed so far when editing an
+    // item with possible values
+    choice_search: String,
+    // the keys typed so far of a key sequence
+    pending_keys: Vec<KeyEvent>,
+    // shown under the footer until the next action
+    message: Option<String>,
+    show_help: bool,
+    // inline, the list uses all the rows it gets
+    inline: bool,
+    list_height: u16,
+    help_height: u16,
+    max_cursor: u16,
+    max_width: u16,
+    term_y: u16,
+}
+
+impl ListState {
+    fn new(out_vec: &[ListItem], term_x: u16, term_y: u16, inline: bool) -> ListState {
+        let mut state = ListState {
+            cursor_position: 0,
+            cursor_offset: 0,
+            editing: false,
+            choice_search: String::new(),
+            pending_keys: vec![],
+            message: None,
+            show_help: true,
+            inline,
+            list_height: 0,
+            help_height: 0,
+            max_cursor: 0,
+            max_width: 0,
+            term_y: 0,
+        };
+        state.resize(out_vec, term_x, term_y);
+        state
+    }
+
+    // recomputes the list and help areas for the terminal size,
+    // and scrolls so the highlighted item is still visible
+    fn resize(&mut self, out_vec: &[ListItem], term_x: u16, term_y: u16) {
+        let index = self.get_index();
+
+        self.max_width = term_x;
+        self.term_y = term_y;
+        // the last two rows are for the footer and the messages
+        if self.inline {
+            self.list_height = term_y.saturating_sub(2);
+            self.help_height = get_help_height(self.list_height, self.show_help, 0);
+        } else {
+            self.list_height = std::cmp::min(term_y - (term_y / 10), term_y.saturating_sub(2));
+            self.help_height = get_help_height(self.list_height, self.show_help, HELP_HEIGHT);
+        }
+        self.max_cursor = self.list_height - self.help_height;
+
+        self.scroll_to(out_vec, index);
+    }
+
+    fn get_index(&self) -> usize {
+        self.cursor_position as usize + self.cursor_offset
+    }
+
+    // scrolls so that the item at index is highlighted, keeping
+    // the items above and below it visible when possible. the
+    // items take the rows they wrap into.
+    fn scroll_to(&mut self, out_vec: &[ListItem], index: usize) {
+        let max_width = self.max_width;
+        let get_height = |num: usize| match out_vec.get(num) {
+            Some(item) => get_wrapped_height(&item.get_visible_text(num == index), max_width) as usize,
+            None => 0,
+        };
+        if index < self.cursor_offset + 1 {
+            self.cursor_offset = index.saturating_sub(1);
+        }
+        let mut rows: usize = (self.cursor_offset..=index + 1).map(get_height).sum();
+        while self.cursor_offset < index && rows > self.max_cursor as usize {
+            rows -= get_height(self.cursor_offset);
+            self.cursor_offset += 1;
+        }
+        self.cursor_position = (index - self.cursor_offset) as i32;
+    }
+}
+
+// called with every action before interact handles it, along
+// with the items and the index of the highlighted item.
+// it returns the action interact should handle, so it can
+// replace or ignore actions (return Action::None), and handle
+// the Action::Custom actions from the keymap.
+pub type ActionHandler<'a> = Box<dyn FnMut(Action, &mut [ListItem], usize) -> Action + 'a>;
+
+// everything interact can be given besides the items. the
+// fields that are not set keep their defaults, eg:
+//
+// let events = Events::new();
+// let sender = events.sender();
+// thread::spawn(move || {
+//     sender.send(InteractEvent::SetAnswer("name".to_string(), "value".to_string()))
+// });
+// interact_with_options(&mut items, InteractOptions {
+//     keymap: get_keymap_from_yaml(&yaml)?,
+//     events,
+//     mode: ScreenMode::Inline,
+//     ..InteractOptions::default()
+// })?;
+pub struct InteractOptions<'a> {
+    pub keymap: Keymap,
+    // by default, every action is handled as it is
+    pub handler: ActionHandler<'a>,
+    // the events other threads send with the senders of the events
+    pub events: Events,
+    pub mode: ScreenMode,
+    // used when there is no terminal, eg: when the input is piped
+    pub fallback: Fallback,
+    // the screen and the input, eg: a MemoryBackend in tests.
+    // by default, the terminal with crossterm.
+    pub backend: Option<&'a mut dyn Backend>,
+}
+
+impl Default for InteractOptions<'_> {
+    fn default() -> Self {
+        InteractOptions {
+            keymap: Keymap::default(),
+            handler: Box::new(|action, _, _| action),
+            events: Events::new(),
+            mode: ScreenMode::FullScreen,
+            fallback: Fallback::Prompt,
+            backend: None,
+        }
+    }
+}
+
+pub fn interact(out_vec: &mut [ListItem]) -> io::Result<InteractResult> {
+    interact_with_options(out_vec, InteractOptions::default())
+}
+
+pub fn interact_with_options(
+    out_vec: &mut [ListItem],
+    options: InteractOptions,
+) -> io::Result<InteractResult> {
+    let InteractOptions { keymap, mut handler, events, mode, fallback, backend } = options;
+    let mut crossterm_backend;
+    let backend = match backend {
+        Some(backend) => backend,
+        None => {
+            crossterm_backend = CrosstermBackend::new();
+            &mut crossterm_backend
+        },
+    };
+
+    let original_items = out_vec.to_vec();
+
     // make sure to enable raw mode, this will make sure key events won't be handled by the terminal it's self and allows crossterm to read the input and pass it back to you.
-    if let Ok(_raw) = RawScreen::into_raw_mode() {
-        let input = input();
-
-        let mut async_stdin = input.read_async();
-        let mut cursor_position = 0;
-        let mut cursor_offset = 0;
-        let mut current_mode = 0; // scroll mode, 1 is editing
-
-        let crossterm = Crossterm::new();
-        let terminal = crossterm.terminal();
-        let cursor = crossterm.cursor();
-        let (term_x, term_y) = terminal.terminal_size();
-
-        terminal.clear(ClearType::All)?;
-
-        let min_cursor = 0;
-        let max_cursor = term_y - (term_y / 10);
-        let max_width = term_x;
-        let out_vec_len = out_vec.len();
-
-        cursor.hide()?;
-
-        print_list(
-            &cursor,
-            max_cursor,
-            max_width,
-            out_vec,
-            cursor_offset,
-            cursor_position
-        )?;
-        cursor.goto(0, term_y - 2)?;
-        println!("CTRL-g to quit. CTRL-w to edit");
-
-        loop {
-            let event = async_stdin.next();
-            if let Some(key_event) = event {
-                if current_mode == 1 {
-                  let editing_item = &mut out_vec[(cursor_position as usize) + (cursor_offset as usize)];
-                  match get_key_char(key_event) {
-                    KeyCharPressed::Char(c) => {
-                      editing_item.answer.push(c);
-                    },
-                    KeyCharPressed::Backspace => {
-                      editing_item.answer.pop();
-                    },
-                    KeyCharPressed::Exit => {
-                      current_mode = 0;
-                      terminal.clear(ClearType::All)?;
-                      print_list(
-                            &cursor,
-                            max_cursor,
-                            max_width,
-                            out_vec,
-                            cursor_offset,
-                            cursor_position
-                      )?;
-                      cursor.goto(0, term_y - 2)?;
-                      println!("CTRL-g to quit. CTRL-w to edit");
-                      continue
-                    },
-                    KeyCharPressed::Quit => {
-                      cursor.show()?;
-                      break;
-                    },
-                    KeyCharPressed::None => {
-                      continue
-                    },
-                  }
-
-                  let my_str = format!(
-                    "{}{}{}",
-                    editing_item.question_style.get_console_string(&editing_item.question, false),
-                    editing_item.delimiter_style.get_console_string(&editing_item.delimiter_style.text, false),
-                    editing_item.answer_style.get_console_string(&editing_item.answer, false),
-                  );
-
-                  let my_str_highlighted = format!(
-                    "{}{}{}{}",
-                    editing_item.prefix_style.get_console_string(&editing_item.prefix_style.text, true),
-                    editing_item.question_style.get_console_string(&editing_item.question, true),
-                    editing_item.delimiter_style.get_console_string(&editing_item.delimiter_style.text, true),
-                    editing_item.answer_style.get_console_string(&editing_item.answer, true),
-                  );
-                  editing_item.regular_output = my_str;
-                  editing_item.highlighted_output = my_str_highlighted;
-
-                  terminal.clear(ClearType::All)?;
-
-                  print_list(
-                      &cursor,
-                      max_cursor,
-                      max_width,
-                      out_vec,
-                      cursor_offset,
-                      cursor_position
-                  )?;
-                  cursor.goto(0, term_y - 2)?;
-                  println!("CTRL-g to quit. CTRL-w to stop editing");
-                  continue
+    // the crossterm backend also switches to the alternate screen in
+    // full screen mode, and restores the terminal when it leaves.
+    if let Err(e) = backend.enter(mode) {
+        return match fallback {
+            Fallback::Prompt => {
+                // the prompts are not part of the output, which
+                // can be piped to another program
+                let stdin = io::stdin();
+                prompt_list_items(out_vec, &mut stdin.lock(), &mut io::stderr())
+            },
+            Fallback::Error => Err(io::Error::new(
+                e.kind(),
+                format!("can not interact without a terminal: {}", e),
+            )),
+        };
+    }
+
+    let result = interact_loop(out_vec, &original_items, &keymap, &mut *handler, &events, mode, backend);
+    // the input is read in raw mode only, and the next
+    // interact, or whatever runs after it, gets the keys
+    events.stop_input();
+    let left = backend.leave();
+    let cancelled = result?;
+    left?;
+
+    if cancelled {
+        out_vec.clone_from_slice(&original_items);
+        return Ok(InteractResult::Cancelled);
+    }
+
+    if mode == ScreenMode::Inline {
+        // the answers stay in the scrollback, without the help and footer
+        for item in out_vec.iter() {
+            backend.write(&format!("{}\n", item.get_output(false)))?;
+        }
+        backend.flush()?;
+    }
+
+    Ok(InteractResult::Submitted(get_changed_items(&original_items, out_vec)))
+}
+
+// handles the events until the answers are submitted or
+// cancelled, and returns if they were cancelled
+fn interact_loop(
+    out_vec: &mut [ListItem],
+    original_items: &[ListItem],
+    keymap: &Keymap,
+    handler: &mut dyn FnMut(Action, &mut [ListItem], usize) -> Action,
+    events: &Events,
+    mode: ScreenMode,
+    backend: &mut dyn Backend,
+) -> io::Result<bool> {
+    let mut cancelled = false;
+    let mut term_size = backend.size();
+    let (term_x, term_y) = term_size;
+
+    let min_cursor = 0;
+    let out_vec_len = out_vec.len();
+
+    // show the errors of answers that are already invalid
+    for item in out_vec.iter_mut() {
+        item.validate();
+        item.update_output();
+    }
+
+    // the cursor position is read from the input, so
+    // this has to happen before the input is read
+    let (mut state, mut renderer) = match mode {
+        ScreenMode::FullScreen => (ListState::new(out_vec, term_x, term_y, false), Renderer::new()),
+        ScreenMode::Inline => {
+            let height = get_inline_height(out_vec, term_x, term_y);
+            let origin = get_inline_origin(backend, height, term_y)?;
+            (ListState::new(out_vec, term_x, height, true), Renderer::inline(origin))
+        },
+    };
+
+    backend.read_input(events);
+    backend.hide_cursor()?;
+
+    draw(&mut renderer, backend, out_vec, &state, keymap)?;
+
+    loop {
+        let event = events.next(Some(Duration::from_millis(RESIZE_CHECK_MILLIS)));
+
+        // there are no resize events, so the size is checked
+        // after every event and timeout, and everything is
+        // printed again when it changes
+        if backend.size() != term_size {
+            term_size = backend.size();
+            let (term_x, term_y) = term_size;
+            match mode {
+                ScreenMode::FullScreen => state.resize(out_vec, term_x, term_y),
+                ScreenMode::Inline => {
+                    renderer.clear(backend)?;
+                    let height = get_inline_height(out_vec, term_x, term_y);
+                    let origin = std::cmp::min(renderer.get_origin(), term_y - height);
+                    renderer.set_origin(origin);
+                    state.resize(out_vec, term_x, height);
+                },
+            }
+            renderer.invalidate();
+            draw(&mut renderer, backend, out_vec, &state, keymap)?;
+        }
+
+        let action = match event {
+            Some(InteractEvent::Input(input_event)) => {
+                get_action(input_event, keymap, state.editing, &mut state.pending_keys)
+            },
+            Some(InteractEvent::Action(action)) => action,
+            Some(InteractEvent::SetAnswer(question, answer)) => {
+                if let Some(item) = out_vec.iter_mut().find(|item| item.question == question) {
+                    item.set_answer(&answer);
+                }
+                draw(&mut renderer, backend, out_vec, &state, keymap)?;
+                continue
+            },
+            Some(InteractEvent::Update(update)) => {
+                update(out_vec);
+                for item in out_vec.iter_mut() {
+                    item.validate();
+                    item.update_output();
                 }
+                draw(&mut renderer, backend, out_vec, &state, keymap)?;
+                continue
+            },
+            Some(InteractEvent::Redraw) => {
+                renderer.invalidate();
+                draw(&mut renderer, backend, out_vec, &state, keymap)?;
+                continue
+            },
+            None => continue,
+        };
 
-                let key_dir = down_or_up(key_event);
-                if key_dir == 255 {
-                    cursor.show()?;
+        if out_vec_len == 0 {
+            // nothing to answer, only submit and cancel do something
+            match action {
+                Action::Submit => break,
+                Action::Cancel => {
+                    cancelled = true;
                     break;
-                } else if key_dir == 254 {
-                  // switch to editing mode.
-                  current_mode = 1;
-                  terminal.clear(ClearType::All)?;
-                  print_list(
-                        &cursor,
-                        max_cursor,
-                        max_width,
-                        out_vec,
-                        cursor_offset,
-                        cursor_position
-                  )?;
-                  cursor.goto(0, term_y - 2)?;
-                  println!("CTRL-g to quit. CTRL-w to stop editing");
-
-                  // out_vec = &mut out_vec.clone();
-                  // cursor.goto(0, cursor_position as u16 + cursor_offset as u16);
-                  // cursor.show();
-                  continue
-                } else if cursor_position + key_dir < max_cursor as i32 && cursor_position + key_dir >= min_cursor as i32 {
-                    
-                    if ((cursor_position + key_dir + cursor_offset as i32) as usize) < out_vec_len {
-                        cursor_position += key_dir;
-                    }
-
-                    terminal.clear(ClearType::All)?;
-
-                    let highlighted_position = match print_list(
-                        &cursor,
-                        max_cursor,
-                        max_width,
-                        out_vec,
-                        cursor_offset,
-                        cursor_position
-                    ) {
-                      Ok(p) => p,
-                      Err(e) => panic!("ERROR PRINTING LIST: {:?}", e),
-                    };
-
-                    cursor.goto(0, term_y - 2)?;
-                    println!("CTRL-g to quit. CTRL-w to edit");
-
-                    if highlighted_position >= (max_cursor - 1) {
-                        cursor_offset += 1;
-                        cursor_position -= key_dir;
-                    } else if cursor_position == (min_cursor + 1) as i32 && cursor_offset > 0 {
-                        cursor_offset -= 1;
-                        cursor_position -= key_dir;
-                    }
+                },
+                _ => continue,
+            }
+        }
+
+        let index = state.get_index();
+        let action = handler(action, out_vec, index);
+        if action != Action::None {
+            state.message = None;
+        }
+
+        match action {
+            Action::Submit => {
+                if !all_list_items_valid(out_vec) {
+                    state.message = Some("some answers are invalid, fix them before submitting".to_string());
+                    draw(&mut renderer, backend, out_vec, &state, keymap)?;
+                    continue
+                }
+                break;
+            },
+            Action::Cancel => {
+                cancelled = true;
+                break;
+            },
+            Action::Edit => {
+                // switch between scroll and editing mode
+                state.editing = !state.editing;
+                state.choice_search.clear();
+                out_vec[index].move_edit_cursor_to_end();
+            },
+            Action::StartEditing | Action::StopEditing => {
+                let editing = action == Action::StartEditing;
+                if state.editing == editing {
+                    continue
+                }
+                state.editing = editing;
+                state.choice_search.clear();
+                out_vec[index].move_edit_cursor_to_end();
+            },
+            Action::ChangeAnswer if !state.editing => {
+                let item = &mut out_vec[index];
+                if !item.info.is_flag {
+                    item.get_edit_text_mut().clear();
+                    item.validate();
+                    item.update_output();
+                }
+                item.move_edit_cursor_to_end();
+                state.editing = true;
+                state.choice_search.clear();
+            },
+            Action::ClearAnswer if !state.editing => {
+                let item = &mut out_vec[index];
+                if item.info.is_flag {
+                    item.set_flag_count(0);
+                } else if item.is_multiple() {
+                    remove_value(item);
+                } else {
+                    item.answer.clear();
+                }
+                item.move_edit_cursor_to_end();
+                item.validate();
+                item.update_output();
+            },
+            Action::RevertItem if !state.editing => {
+                out_vec[index] = original_items[index].clone();
+                out_vec[index].validate();
+                out_vec[index].update_output();
+            },
+            Action::ToggleHelp if !state.editing => {
+                // toggle the help pane, and make sure the
+                // highlighted item is still visible.
+                state.show_help = !state.show_help;
+                state.resize(out_vec, state.max_width, state.term_y);
+            },
+            Action::ToggleFlag if !state.editing => {
+                // space toggles flags without entering edit mode
+                let item = &mut out_vec[index];
+                if !item.info.is_flag {
+                    continue
+                }
+                item.toggle_flag();
+            },
+            Action::Top if !state.editing => state.scroll_to(out_vec, 0),
+            Action::Bottom if !state.editing => state.scroll_to(out_vec, out_vec_len - 1),
+            Action::PageUp | Action::PageDown if !state.editing => {
+                let page = (state.max_cursor as usize).saturating_sub(2).max(1);
+                let next_index = if action == Action::PageUp {
+                    index.saturating_sub(page)
+                } else {
+                    (index + page).min(out_vec_len - 1)
+                };
+                state.scroll_to(out_vec, next_index);
+            },
+            Action::MoveUp | Action::MoveDown if !state.editing => {
+                let key_dir = if action == Action::MoveUp { -1 } else { 1 };
+                let cursor_position = state.cursor_position;
+                if cursor_position + key_dir >= state.max_cursor as i32 || cursor_position + key_dir < min_cursor {
+                    continue
                 }
+
+                if ((cursor_position + key_dir + state.cursor_offset as i32) as usize) < out_vec_len {
+                    state.cursor_position += key_dir;
+                }
+
+                let highlighted_position = draw(&mut renderer, backend, out_vec, &state, keymap)?;
+
+                if highlighted_position >= state.max_cursor.saturating_sub(1) {
+                    state.cursor_offset += 1;
+                    state.cursor_position -= key_dir;
+                } else if state.cursor_position == min_cursor + 1 && state.cursor_offset > 0 {
+                    state.cursor_offset -= 1;
+                    state.cursor_position -= key_dir;
+                }
+                continue
+            },
+            Action::Custom(_) | Action::None => continue,
+            action => {
+                if !state.editing || !edit_list_item(&mut out_vec[index], &action, &mut state.choice_search) {
+                    continue
+                }
+            },
+        }
+
+        draw(&mut renderer, backend, out_vec, &state, keymap)?;
+    }
+    if mode == ScreenMode::Inline {
+        renderer.clear(backend)?;
+    }
+
+    Ok(cancelled)
+}
+
+// renders the list, the help pane and the footer. only the rows
+// that changed since the last draw are printed again.
+// returns the row of the highlighted item.
+fn draw(
+    renderer: &mut Renderer,
+    backend: &mut dyn Backend,
+    out_vec: &[ListItem],
+    state: &ListState,
+    keymap: &Keymap,
+) -> io::Result<u16> {
+    let mut frame = Frame::new(state.term_y);
+
+    let highlighted_position = add_list_to_frame(
+        &mut frame,
+        state.max_cursor,
+        state.max_width,
+        out_vec,
+        state.cursor_offset,
+        state.cursor_position
+    );
+    add_help_to_frame(
+        &mut frame,
+        state.max_cursor,
+        state.help_height,
+        state.max_width,
+        out_vec.get(state.get_index()),
+        if state.editing { Some(keymap) } else { None },
+    );
+
+    let item = out_vec.get(state.get_index());
+    let footer = if state.editing {
+        get_edit_footer(keymap)
+    } else {
+        get_footer(keymap)
+    };
+    // the footer and the messages get a row each, text that
+    // wrapped would scroll the screen
+    frame.set_line(state.term_y.saturating_sub(2), &truncate_to_width(&footer, state.max_width), 1);
+    if let Some(message) = &state.message {
+        frame.set_line(state.term_y.saturating_sub(1), &truncate_to_width(message, state.max_width), 1);
+    }
+
+    renderer.render(backend, frame)?;
+
+    match item {
+        Some(item) if state.editing && !item.info.is_flag && !has_choices(item) => {
+            // show the cursor where the answer is edited. the
+            // highlighted position is the last row of the item.
+            let column = item.get_edit_column() as u16;
+            let max_width = state.max_width.max(1);
+            let height = get_wrapped_height(&item.get_visible_text(true), max_width);
+            let first_row = (highlighted_position + 1).saturating_sub(height);
+            let row = renderer.get_origin() + first_row + column / max_width;
+            backend.goto(column % max_width, row)?;
+            backend.show_cursor()?;
+        },
+        _ => backend.hide_cursor()?,
+    }
+
+    Ok(highlighted_position)
+}
+
+// the help pane takes part of the list area, but
+// always leaves a few rows for the list itself
+fn get_help_height(list_height: u16, show_help: bool, min_list_height: u16) -> u16 {
+    if show_help && list_height > HELP_HEIGHT + min_list_height {
+        HELP_HEIGHT
+    } else {
+        0
+    }
+}
+
+// inline, the rows for all of the items, the help pane,
+// the footer and the messages, as far as they fit
+fn get_inline_height(out_vec: &[ListItem], max_width: u16, term_y: u16) -> u16 {
+    let list_height: u16 = out_vec.iter()
+        .map(|item| get_wrapped_height(&item.get_visible_text(true), max_width))
+        .sum();
+    std::cmp::min(list_height + HELP_HEIGHT + 2, term_y)
+}
+
+// the row the inline list starts at: the one under the cursor,
+// after scrolling the terminal up if there are not enough rows left
+fn get_inline_origin(backend: &mut dyn Backend, height: u16, term_y: u16) -> io::Result<u16> {
+    let (x, y) = backend.cursor_pos();
+    let y = if x > 0 { y + 1 } else { y };
+    if y + height <= term_y {
+        return Ok(y);
+    }
+
+    backend.goto(0, term_y.saturating_sub(1))?;
+    backend.write(&"\n".repeat((y + height - term_y) as usize))?;
+    backend.flush()?;
+    Ok(term_y - height)
+}
+
+fn get_footer(keymap: &Keymap) -> String {
+    format!(
+        "{} to submit. {} to cancel. {} to edit. {} to toggle help",
+        keymap.describe(Action::Submit),
+        keymap.describe(Action::Cancel),
+        keymap.describe_any(&[Action::Edit, Action::StartEditing], false),
+        keymap.describe(Action::ToggleHelp),
+    )
+}
+
+// the keys that only edit some items are in the help pane
+fn get_edit_footer(keymap: &Keymap) -> String {
+    format!(
+        "{} to submit. {} to cancel. {} to stop editing",
+        keymap.describe(Action::Submit),
+        keymap.describe(Action::Cancel),
+        keymap.describe_any(&[Action::Edit, Action::StopEditing], true),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::get_styles_from_yaml;
+    use yaml_rust::Yaml;
+    use super::super::{MemoryBackend, get_list_layout};
+
+    fn get_items(count: usize, start: usize) -> Vec<ListItem> {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        (start..start + count)
+            .map(|i| ListItem::from_styles(&format!("q{}", i), &format!("a{}", i), &styles))
+            .collect()
+    }
+
+    #[test]
+    fn get_changed_items_works() {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let original = vec![
+            ListItem::from_styles("a", "1", &styles),
+            ListItem::from_styles("b", "2", &styles),
+            ListItem::from_styles("c", "3", &styles),
+        ];
+        let mut current = original.clone();
+        assert!(get_changed_items(&original, &current).is_empty());
+
+        current[0].answer = "changed".to_string();
+        current[2].values.push("new value".to_string());
+        assert_eq!(get_changed_items(&original, &current), vec![0, 2]);
+    }
+
+    #[test]
+    fn resize_keeps_the_highlighted_item_visible() {
+        let items = get_items(30, 0);
+        let mut state = ListState::new(&items, 80, 40, false);
+        assert_eq!(state.list_height, 36);
+        assert_eq!(state.max_cursor, 31);
+
+        state.scroll_to(&items, 25);
+        assert_eq!(state.get_index(), 25);
+        assert_eq!(state.cursor_offset, 0);
+
+        state.resize(&items, 80, 20);
+        assert_eq!(state.get_index(), 25);
+        assert!(state.cursor_position < state.max_cursor as i32 - 1);
+        assert_eq!(state.max_width, 80);
+
+        // too small for the help pane, and for anything
+        state.resize(&items, 10, 8);
+        assert_eq!(state.help_height, 0);
+        assert_eq!(state.get_index(), 25);
+        state.resize(&items, 0, 0);
+        assert_eq!(state.max_cursor, 0);
+        state.resize(&items, 80, 40);
+        assert_eq!(state.help_height, 5);
+
+        // inline, the list gets every row but the footer and messages
+        let state = ListState::new(&items, 80, 10, true);
+        assert_eq!(state.list_height, 8);
+        assert_eq!(state.max_cursor, 3);
+        assert_eq!(ListState::new(&items, 80, 6, true).help_height, 0);
+    }
+
+    #[test]
+    fn scroll_to_keeps_wrapped_items_visible() {
+        // items 3 to 10 wrap into 3 rows each
+        let mut items = get_items(20, 0);
+        for item in items[3..=10].iter_mut() {
+            item.set_answer(&"a".repeat(230));
+        }
+        let mut state = ListState::new(&items, 80, 24, false);
+        assert_eq!(state.max_cursor, 17);
+
+        let get_rows = |state: &ListState, index: usize| {
+            let layout = get_list_layout(&items, state.cursor_offset, state.cursor_position, state.max_cursor, 80);
+            layout.iter().find(|l| l.index == index).map(|l| (l.row, l.row + l.height))
+        };
+        for &index in [11, 10, 5, 0, 19].iter() {
+            state.scroll_to(&items, index);
+            assert_eq!(state.get_index(), index);
+            // the highlighted item, and the one below it, are on the screen
+            let (_, end) = get_rows(&state, index).unwrap();
+            assert!(end <= state.max_cursor);
+            if index + 1 < items.len() {
+                assert!(get_rows(&state, index + 1).unwrap().1 <= state.max_cursor);
             }
-            thread::sleep(Duration::from_millis(15));
         }
-    } // <=== raw modes will be disabled here
 
-    Ok(())
+        // the list is not scrolled further than it has to
+        state.scroll_to(&items, 0);
+        state.scroll_to(&items, 4);
+        assert_eq!(state.cursor_offset, 0);
+        state.scroll_to(&items, 11);
+        assert_eq!(state.cursor_offset, 6);
+    }
+
+    #[test]
+    fn interact_with_options_works() {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut items = vec![
+            ListItem::from_styles("a", "1", &styles),
+            ListItem::from_styles("b", "2", &styles),
+        ];
+        let mut backend = MemoryBackend::new(40, 12);
+        backend.add_keys(&[
+            KeyEvent::Down,
+            KeyEvent::Ctrl('w'),
+            KeyEvent::Backspace,
+            KeyEvent::Char('x'),
+            KeyEvent::Ctrl('g'),
+        ]);
+
+        let result = interact_with_options(&mut items, InteractOptions {
+            fallback: Fallback::Error,
+            backend: Some(&mut backend),
+            ..InteractOptions::default()
+        }).unwrap();
+        assert_eq!(result, InteractResult::Submitted(vec![1]));
+        assert_eq!(items[1].answer, "x");
+        assert!(backend.get_lines()[1].contains('x'));
+        assert!(backend.get_lines()[10].starts_with("CTRL-g to submit"));
+
+        // the keys ran out before submitting. the handler gets
+        // every action first, the custom ones too.
+        let mut backend = MemoryBackend::new(40, 12);
+        backend.add_keys(&[KeyEvent::Ctrl('w'), KeyEvent::Char('y'), KeyEvent::Ctrl('s')]);
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::Custom("save".to_string()), vec![vec![KeyEvent::Ctrl('s')]]);
+        let mut handled = vec![];
+        let result = interact_with_options(&mut items, InteractOptions {
+            keymap,
+            handler: Box::new(|action, _, _| {
+                handled.push(action.clone());
+                action
+            }),
+            fallback: Fallback::Error,
+            backend: Some(&mut backend),
+            ..InteractOptions::default()
+        }).unwrap();
+        assert_eq!(result, InteractResult::Cancelled);
+        assert_eq!(items[0].answer, "1");
+        assert_eq!(handled, vec![
+            Action::Edit,
+            Action::InsertChar('y'),
+            Action::Custom("save".to_string()),
+            Action::Cancel,
+        ]);
+    }
+
+    #[test]
+    fn interact_inline_works() {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut items = vec![
+            ListItem::from_styles("a", "1", &styles),
+            ListItem::from_styles("b", "2", &styles),
+        ];
+        // the list, the help pane, the footer and the messages take 9 rows
+        let mut backend = MemoryBackend::new(40, 14);
+        for i in 0..10 {
+            backend.write(&format!("line {}\n", i)).unwrap();
+        }
+        backend.add_keys(&[KeyEvent::Down]);
+        backend.add_resize(40, 12);
+        backend.add_keys(&[KeyEvent::Ctrl('w'), KeyEvent::Char('x'), KeyEvent::Ctrl('g')]);
+
+        let result = interact_with_options(&mut items, InteractOptions {
+            mode: ScreenMode::Inline,
+            fallback: Fallback::Error,
+            backend: Some(&mut backend),
+            ..InteractOptions::default()
+        }).unwrap();
+        assert_eq!(result, InteractResult::Submitted(vec![1]));
+
+        // there were 4 rows under the cursor, so the
+        // terminal scrolled up 5 rows to fit the list
+        let frames = backend.get_frames();
+        let first_frame: Vec<&str> = frames[1].lines().collect();
+        assert_eq!(first_frame[0], "line 5");
+        assert_eq!(first_frame[4], "line 9");
+        assert_eq!(first_frame[5], "a: 1");
+
+        // after the resize, the list moved up to fit in 12 rows
+        let resized = frames.iter().rev()
+            .find(|frame| frame.contains("b: 2x") && frame.contains("CTRL-g"))
+            .unwrap();
+        let resized: Vec<&str> = resized.lines().collect();
+        assert_eq!(resized[3], "a: 1");
+        assert!(resized[10].starts_with("CTRL-g to submit"));
+
+        // the list is cleared and the answers are left in the scrollback
+        let lines = backend.get_lines();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(&lines[..6], ["line 5", "line 6", "line 7", "a: 1", "b: 2x", ""]);
+        assert!(lines[6..].iter().all(|line| line.is_empty()));
+        assert_eq!(backend.get_cursor(), (0, 5));
+    }
 }
@@ -0,0 +1,549 @@
+use crossterm::KeyEvent;
+use yaml_rust::{Yaml};
+
+// what a key press does. every input event is translated
+// into one of these by get_action before it is handled.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    PageUp,
+    PageDown,
+    // the first and the last item
+    Top,
+    Bottom,
+    // enter or leave edit mode
+    Edit,
+    StartEditing,
+    StopEditing,
+    // clear the answer and start editing it
+    ChangeAnswer,
+    // clear the answer, or remove the selected
+    // value of an arg that takes multiple values
+    ClearAnswer,
+    // restore the answer the item had before interact
+    RevertItem,
+    Submit,
+    Cancel,
+    InsertChar(char),
+    Backspace,
+    // moving the cursor and deleting text
+    // in the answer that is being edited
+    Delete,
+    CursorLeft,
+    CursorRight,
+    CursorHome,
+    CursorEnd,
+    WordLeft,
+    WordRight,
+    DeleteToEnd,
+    DeleteToStart,
+    DeleteWordBack,
+    ToggleHelp,
+    ToggleFlag,
+    NewValue,
+    DeleteValue,
+    MoveValueUp,
+    MoveValueDown,
+    // not handled by interact, for the action handler of downstream crates
+    Custom(String),
+    None,
+}
+
+impl Action {
+    // the name used in the interactive_keys section of the yaml.
+    // custom actions are named custom:<name>, so a misspelled
+    // name is not taken for one.
+    pub fn from_name(name: &str) -> Option<Action> {
+        if let Some(custom) = name.strip_prefix("custom:") {
+            return Some(Action::Custom(custom.to_string()));
+        }
+
+        let action = match name {
+            "submit" => Action::Submit,
+            "cancel" => Action::Cancel,
+            "edit" => Action::Edit,
+            "start_editing" => Action::StartEditing,
+            "stop_editing" => Action::StopEditing,
+            "change" => Action::ChangeAnswer,
+            "clear" => Action::ClearAnswer,
+            "revert" => Action::RevertItem,
+            "help" => Action::ToggleHelp,
+            "toggle" => Action::ToggleFlag,
+            "up" => Action::MoveUp,
+            "down" => Action::MoveDown,
+            "page_up" => Action::PageUp,
+            "page_down" => Action::PageDown,
+            "top" => Action::Top,
+            "bottom" => Action::Bottom,
+            "backspace" => Action::Backspace,
+            "delete" => Action::Delete,
+            "cursor_left" => Action::CursorLeft,
+            "cursor_right" => Action::CursorRight,
+            "home" => Action::CursorHome,
+            "end" => Action::CursorEnd,
+            "word_left" => Action::WordLeft,
+            "word_right" => Action::WordRight,
+            "delete_to_end" => Action::DeleteToEnd,
+            "delete_to_start" => Action::DeleteToStart,
+            "delete_word" => Action::DeleteWordBack,
+            "new_value" => Action::NewValue,
+            "delete_value" => Action::DeleteValue,
+            "move_value_up" => Action::MoveValueUp,
+            "move_value_down" => Action::MoveValueDown,
+            _ => return None,
+        };
+        Some(action)
+    }
+
+    // actions that only do something in edit mode. their
+    // keys are bound in the edit bindings of the keymap.
+    pub fn is_line_edit(&self) -> bool {
+        matches!(self, Action::Delete
+            | Action::CursorLeft
+            | Action::CursorRight
+            | Action::CursorHome
+            | Action::CursorEnd
+            | Action::WordLeft
+            | Action::WordRight
+            | Action::DeleteToEnd
+            | Action::DeleteToStart
+            | Action::DeleteWordBack)
+    }
+}
+
+// parses key names like: ctrl-g, alt-b, up, pagedown, esc, space, f1, ?
+pub fn parse_key(key: &str) -> Option<KeyEvent> {
+    let lower = key.to_lowercase();
+    let single_char = |s: &str| {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    };
+
+    if let Some(rest) = lower.strip_prefix("ctrl-") {
+        return match rest {
+            "up" => Some(KeyEvent::CtrlUp),
+            "down" => Some(KeyEvent::CtrlDown),
+            "left" => Some(KeyEvent::CtrlLeft),
+            "right" => Some(KeyEvent::CtrlRight),
+            _ => single_char(rest).map(KeyEvent::Ctrl),
+        };
+    }
+    if let Some(rest) = lower.strip_prefix("alt-") {
+        return single_char(rest).map(KeyEvent::Alt);
+    }
+    if let Some(rest) = lower.strip_prefix("shift-") {
+        return match rest {
+            "up" => Some(KeyEvent::ShiftUp),
+            "down" => Some(KeyEvent::ShiftDown),
+            "left" => Some(KeyEvent::ShiftLeft),
+            "right" => Some(KeyEvent::ShiftRight),
+            "tab" => Some(KeyEvent::BackTab),
+            _ => None,
+        };
+    }
+
+    match lower.as_str() {
+        "up" => Some(KeyEvent::Up),
+        "down" => Some(KeyEvent::Down),
+        "left" => Some(KeyEvent::Left),
+        "right" => Some(KeyEvent::Right),
+        "home" => Some(KeyEvent::Home),
+        "end" => Some(KeyEvent::End),
+        "pageup" | "pgup" => Some(KeyEvent::PageUp),
+        "pagedown" | "pgdn" => Some(KeyEvent::PageDown),
+        "backspace" => Some(KeyEvent::Backspace),
+        "delete" | "del" => Some(KeyEvent::Delete),
+        "insert" | "ins" => Some(KeyEvent::Insert),
+        "esc" | "escape" => Some(KeyEvent::Esc),
+        "enter" | "return" => Some(KeyEvent::Char('\n')),
+        "tab" => Some(KeyEvent::Char('\t')),
+        "space" => Some(KeyEvent::Char(' ')),
+        _ => {
+            if lower.len() > 1 && lower.starts_with('f') {
+                return lower[1..].parse().ok().map(KeyEvent::F);
+            }
+            // single chars keep their case, eg: G and g are different keys
+            single_char(key).map(KeyEvent::Char)
+        },
+    }
+}
+
+// parses a key or a sequence of keys, like: ctrl-g, gg, :wq, g g.
+// the keys of a sequence are separated by spaces, or written
+// together when they are plain chars: two letters, or chars that
+// are not all letters. longer words are key names, so a misspelled
+// name like pgupp is an error and not the keys p, g, u, p, p.
+pub fn parse_keys(keys: &str) -> Option<Vec<KeyEvent>> {
+    let mut sequence = vec![];
+
+    for name in keys.split_whitespace() {
+        let is_word = name.chars().all(|c| c.is_alphabetic() || c == '-');
+        match parse_key(name) {
+            Some(key) => sequence.push(key),
+            None if !is_word || name.chars().count() <= 2 => {
+                sequence.extend(name.chars().map(KeyEvent::Char))
+            },
+            None => return None,
+        }
+    }
+
+    if sequence.is_empty() {
+        None
+    } else {
+        Some(sequence)
+    }
+}
+
+// the name of the key as it is shown in the footer
+pub fn get_key_name(key: &KeyEvent) -> String {
+    match key {
+        KeyEvent::Ctrl(c) => format!("CTRL-{}", c),
+        KeyEvent::Alt(c) => format!("ALT-{}", c),
+        KeyEvent::Char(' ') => "Space".to_string(),
+        KeyEvent::Char('\n') => "Enter".to_string(),
+        KeyEvent::Char('\t') => "Tab".to_string(),
+        KeyEvent::Char(c) => c.to_string(),
+        KeyEvent::CtrlUp => "CTRL-Up".to_string(),
+        KeyEvent::CtrlDown => "CTRL-Down".to_string(),
+        KeyEvent::CtrlLeft => "CTRL-Left".to_string(),
+        KeyEvent::CtrlRight => "CTRL-Right".to_string(),
+        KeyEvent::F(n) => format!("F{}", n),
+        k => format!("{:?}", k),
+    }
+}
+
+// every binding is a sequence of keys, most of them only one key long.
+// in edit mode, the edit bindings are used before the others,
+// so ctrl-w can delete a word while editing and start editing otherwise.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    pub bindings: Vec<(Vec<KeyEvent>, Action)>,
+    pub edit_bindings: Vec<(Vec<KeyEvent>, Action)>,
+}
+
+// readline keys for editing the answer
+fn get_default_edit_bindings() -> Vec<(Vec<KeyEvent>, Action)> {
+    vec![
+        (KeyEvent::Left, Action::CursorLeft),
+        (KeyEvent::Ctrl('b'), Action::CursorLeft),
+        (KeyEvent::Right, Action::CursorRight),
+        (KeyEvent::Ctrl('f'), Action::CursorRight),
+        (KeyEvent::Home, Action::CursorHome),
+        (KeyEvent::Ctrl('a'), Action::CursorHome),
+        (KeyEvent::End, Action::CursorEnd),
+        (KeyEvent::Ctrl('e'), Action::CursorEnd),
+        (KeyEvent::Alt('b'), Action::WordLeft),
+        (KeyEvent::CtrlLeft, Action::WordLeft),
+        (KeyEvent::Alt('f'), Action::WordRight),
+        (KeyEvent::CtrlRight, Action::WordRight),
+        (KeyEvent::Delete, Action::Delete),
+        (KeyEvent::Ctrl('k'), Action::DeleteToEnd),
+        (KeyEvent::Ctrl('u'), Action::DeleteToStart),
+        (KeyEvent::Ctrl('w'), Action::DeleteWordBack),
+        (KeyEvent::Esc, Action::StopEditing),
+        (KeyEvent::Char('\n'), Action::StopEditing),
+    ].into_iter().map(|(k, a)| (vec![k], a)).collect()
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::from_bindings(vec![
+            (KeyEvent::Ctrl('g'), Action::Submit),
+            (KeyEvent::Ctrl('c'), Action::Cancel),
+            (KeyEvent::Ctrl('w'), Action::Edit),
+            (KeyEvent::Char('?'), Action::ToggleHelp),
+            (KeyEvent::Char(' '), Action::ToggleFlag),
+            (KeyEvent::Up, Action::MoveUp),
+            (KeyEvent::Down, Action::MoveDown),
+            (KeyEvent::PageUp, Action::PageUp),
+            (KeyEvent::PageDown, Action::PageDown),
+            (KeyEvent::Backspace, Action::Backspace),
+            (KeyEvent::Ctrl('n'), Action::NewValue),
+            (KeyEvent::Ctrl('d'), Action::DeleteValue),
+            (KeyEvent::CtrlUp, Action::MoveValueUp),
+            (KeyEvent::CtrlDown, Action::MoveValueDown),
+        ])
+    }
+}
+
+impl Keymap {
+    fn from_bindings(bindings: Vec<(KeyEvent, Action)>) -> Keymap {
+        Keymap {
+            bindings: bindings.into_iter().map(|(k, a)| (vec![k], a)).collect(),
+            edit_bindings: get_default_edit_bindings(),
+        }
+    }
+
+    // j/k to move, gg/G to jump, i/a/cc to edit, Esc to stop
+    // editing, dd to clear an answer or remove a value,
+    // u to revert an item, :wq to submit and :q! to cancel
+    pub fn vim() -> Keymap {
+        let mut keymap = Keymap::from_bindings(vec![
+            (KeyEvent::Char('k'), Action::MoveUp),
+            (KeyEvent::Up, Action::MoveUp),
+            (KeyEvent::Char('j'), Action::MoveDown),
+            (KeyEvent::Down, Action::MoveDown),
+            (KeyEvent::Ctrl('b'), Action::PageUp),
+            (KeyEvent::PageUp, Action::PageUp),
+            (KeyEvent::Ctrl('f'), Action::PageDown),
+            (KeyEvent::PageDown, Action::PageDown),
+            (KeyEvent::Char('G'), Action::Bottom),
+            (KeyEvent::Char('i'), Action::StartEditing),
+            (KeyEvent::Char('a'), Action::StartEditing),
+            (KeyEvent::Esc, Action::StopEditing),
+            (KeyEvent::Char('u'), Action::RevertItem),
+            (KeyEvent::Ctrl('c'), Action::Cancel),
+            (KeyEvent::Char('?'), Action::ToggleHelp),
+            (KeyEvent::Char(' '), Action::ToggleFlag),
+            (KeyEvent::Backspace, Action::Backspace),
+            (KeyEvent::Ctrl('n'), Action::NewValue),
+            (KeyEvent::Ctrl('d'), Action::DeleteValue),
+            (KeyEvent::CtrlUp, Action::MoveValueUp),
+            (KeyEvent::CtrlDown, Action::MoveValueDown),
+        ]);
+
+        let sequences = vec![
+            ("gg", Action::Top),
+            ("cc", Action::ChangeAnswer),
+            ("dd", Action::ClearAnswer),
+            (":wq", Action::Submit),
+            (":x", Action::Submit),
+            (":q!", Action::Cancel),
+        ];
+        for (keys, action) in sequences {
+            if let Some(keys) = parse_keys(keys) {
+                keymap.bindings.push((keys, action));
+            }
+        }
+
+        keymap
+    }
+
+    fn get_bindings(&self, is_editing: bool) -> impl Iterator<Item = &(Vec<KeyEvent>, Action)> {
+        let edit_bindings = if is_editing { &self.edit_bindings[..] } else { &[] };
+        edit_bindings.iter().chain(self.bindings.iter())
+    }
+
+    // the action of a complete sequence of keys
+    pub fn get_action(&self, keys: &[KeyEvent], is_editing: bool) -> Option<Action> {
+        self.get_bindings(is_editing)
+            .find(|(k, _)| k.as_slice() == keys)
+            .map(|(_, a)| a.clone())
+    }
+
+    // whether the keys are the start of a longer sequence
+    pub fn is_prefix(&self, keys: &[KeyEvent], is_editing: bool) -> bool {
+        self.get_bindings(is_editing)
+            .any(|(k, _)| k.len() > keys.len() && k.starts_with(keys))
+    }
+
+    // the keys that do the action, leaving out the ones that are
+    // used by another action first, and in edit mode the chars,
+    // which are typed into the answer
+    pub fn get_keys(&self, action: Action, is_editing: bool) -> Vec<&[KeyEvent]> {
+        let is_typed = |k: &[KeyEvent]| match k.first() {
+            Some(KeyEvent::Char(c)) => is_editing && !c.is_control(),
+            _ => false,
+        };
+        self.get_bindings(is_editing)
+            .filter(|(k, a)| *a == action && self.get_action(k, is_editing).as_ref() == Some(a))
+            .filter(|(k, _)| !is_typed(k))
+            .map(|(k, _)| k.as_slice())
+            .collect()
+    }
+
+    // replaces every key of the action with the given keys
+    pub fn bind(&mut self, action: Action, keys: Vec<Vec<KeyEvent>>) {
+        self.bindings.retain(|(k, a)| *a != action && !keys.contains(k));
+        self.edit_bindings.retain(|(k, a)| *a != action && !keys.contains(k));
+
+        let bindings = if action.is_line_edit() {
+            &mut self.edit_bindings
+        } else {
+            &mut self.bindings
+        };
+        for key in keys {
+            bindings.push((key, action.clone()));
+        }
+    }
+
+    // the first key of the action, for the footer
+    pub fn describe(&self, action: Action) -> String {
+        self.describe_any(&[action], false)
+    }
+
+    // the first key of the first action that has one
+    pub fn describe_any(&self, actions: &[Action], is_editing: bool) -> String {
+        let keys = actions.iter()
+            .filter_map(|a| self.get_keys(a.clone(), is_editing).first().cloned())
+            .next();
+
+        match keys {
+            Some(keys) => keys.iter().map(get_key_name).collect(),
+            None => "(unbound)".to_string(),
+        }
+    }
+}
+
+// interactive_keymap: vim
+// interactive_keys:
+//   submit: ctrl-s
+//   cancel: [ctrl-c, esc]
+//   edit: ctrl-e
+//   top: gg
+//   custom:my_action: ctrl-x
+// an action or a key that can not be parsed is an error,
+// so a typo does not leave an action without keys
+pub fn get_keymap_from_yaml(yaml_obj: &Yaml) -> Result<Keymap, String> {
+    let mut keymap = match yaml_obj["interactive_keymap"].as_str() {
+        Some("vim") => Keymap::vim(),
+        _ => Keymap::default(),
+    };
+
+    if let Some(h) = yaml_obj["interactive_keys"].as_hash() {
+        for (key, value) in h {
+            let action = match key.as_str() {
+                Some(name) => match Action::from_name(name) {
+                    Some(action) => action,
+                    None => return Err(format!("unknown action \"{}\"", name)),
+                },
+                None => continue,
+            };
+
+            let key_names: Vec<&str> = match value.as_vec() {
+                Some(v) => v.iter().filter_map(|k| k.as_str()).collect(),
+                None => value.as_str().into_iter().collect(),
+            };
+            let mut keys = vec![];
+            for name in key_names {
+                match parse_keys(name) {
+                    Some(key) => keys.push(key),
+                    None => return Err(format!(
+                        "unknown key \"{}\" for {}",
+                        name,
+                        key.as_str().unwrap_or_default(),
+                    )),
+                }
+            }
+            keymap.bind(action, keys);
+        }
+    }
+
+    Ok(keymap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaml_rust::YamlLoader;
+
+    #[test]
+    fn parse_key_works() {
+        assert_eq!(parse_key("ctrl-g"), Some(KeyEvent::Ctrl('g')));
+        assert_eq!(parse_key("CTRL-Up"), Some(KeyEvent::CtrlUp));
+        assert_eq!(parse_key("alt-b"), Some(KeyEvent::Alt('b')));
+        assert_eq!(parse_key("pagedown"), Some(KeyEvent::PageDown));
+        assert_eq!(parse_key("esc"), Some(KeyEvent::Esc));
+        assert_eq!(parse_key("space"), Some(KeyEvent::Char(' ')));
+        assert_eq!(parse_key("f5"), Some(KeyEvent::F(5)));
+        assert_eq!(parse_key("G"), Some(KeyEvent::Char('G')));
+        assert_eq!(parse_key("?"), Some(KeyEvent::Char('?')));
+        assert_eq!(parse_key("ctrl-nope"), None);
+    }
+
+    #[test]
+    fn parse_keys_works() {
+        let chars = |s: &str| s.chars().map(KeyEvent::Char).collect::<Vec<_>>();
+        assert_eq!(parse_keys("ctrl-g"), Some(vec![KeyEvent::Ctrl('g')]));
+        assert_eq!(parse_keys("gg"), Some(chars("gg")));
+        assert_eq!(parse_keys(":wq"), Some(chars(":wq")));
+        assert_eq!(parse_keys("g g"), Some(chars("gg")));
+        assert_eq!(parse_keys("esc :q!"), Some([vec![KeyEvent::Esc], chars(":q!")].concat()));
+        assert_eq!(parse_keys("ctrl-nope"), None);
+        assert_eq!(parse_keys("pgup"), Some(vec![KeyEvent::PageUp]));
+        assert_eq!(parse_keys("pgupp"), None);
+        assert_eq!(parse_keys(""), None);
+    }
+
+    #[test]
+    fn get_keymap_from_yaml_works() {
+        let yaml = YamlLoader::load_from_str("
+interactive_keys:
+  edit: ctrl-e
+  cancel: [ctrl-c, esc]
+  custom:my_action: ctrl-x
+").unwrap();
+
+        let keymap = get_keymap_from_yaml(&yaml[0]).unwrap();
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('e')], false), Some(Action::Edit));
+        // the default edit key is replaced
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('w')], false), None);
+        assert_eq!(keymap.get_action(&[KeyEvent::Esc], false), Some(Action::Cancel));
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('c')], false), Some(Action::Cancel));
+        // custom actions are for downstream handlers
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('x')], false), Some(Action::Custom("my_action".to_string())));
+        // other actions keep their defaults
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('g')], false), Some(Action::Submit));
+        assert_eq!(keymap.describe(Action::Edit), "CTRL-e");
+        // the edit bindings are used first in edit mode,
+        // unless the key was bound in the yaml
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('e')], true), Some(Action::Edit));
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('a')], true), Some(Action::CursorHome));
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('a')], false), None);
+
+        let yaml = YamlLoader::load_from_str("
+interactive_keys:
+  submit: [ctrl-s, ctrl-nope]
+").unwrap();
+        assert_eq!(
+            get_keymap_from_yaml(&yaml[0]).unwrap_err(),
+            "unknown key \"ctrl-nope\" for submit",
+        );
+
+        // a misspelled action does not take the key of the real one
+        let yaml = YamlLoader::load_from_str("
+interactive_keys:
+  sumbit: ctrl-s
+").unwrap();
+        assert_eq!(get_keymap_from_yaml(&yaml[0]).unwrap_err(), "unknown action \"sumbit\"");
+        assert_eq!(Action::from_name("submit"), Some(Action::Submit));
+        assert_eq!(Action::from_name("custom:submit"), Some(Action::Custom("submit".to_string())));
+    }
+
+    #[test]
+    fn edit_bindings_work() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('w')], false), Some(Action::Edit));
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('w')], true), Some(Action::DeleteWordBack));
+        // ctrl-w deletes a word in edit mode, so esc is shown instead
+        assert_eq!(keymap.describe_any(&[Action::Edit, Action::StopEditing], true), "Esc");
+
+        keymap.bind(Action::CursorHome, vec![vec![KeyEvent::Ctrl('h')]]);
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('h')], true), Some(Action::CursorHome));
+        assert_eq!(keymap.get_action(&[KeyEvent::Ctrl('a')], true), None);
+    }
+
+    #[test]
+    fn vim_keymap_works() {
+        let yaml = YamlLoader::load_from_str("
+interactive_keymap: vim
+interactive_keys:
+  bottom: [G, ge]
+").unwrap();
+
+        let keymap = get_keymap_from_yaml(&yaml[0]).unwrap();
+        let g = || KeyEvent::Char('g');
+        assert_eq!(keymap.get_action(&[KeyEvent::Char('j')], false), Some(Action::MoveDown));
+        assert_eq!(keymap.get_action(&[g(), g()], false), Some(Action::Top));
+        assert_eq!(keymap.get_action(&[g(), KeyEvent::Char('e')], false), Some(Action::Bottom));
+        assert_eq!(keymap.get_action(&[g()], false), None);
+        assert!(keymap.is_prefix(&[g()], false));
+        assert!(!keymap.is_prefix(&[g(), g()], false));
+        assert_eq!(keymap.describe(Action::Submit), ":wq");
+        assert_eq!(keymap.describe_any(&[Action::Edit, Action::StartEditing], false), "i");
+    }
+}
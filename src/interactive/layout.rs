@@ -0,0 +1,106 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::super::utils::{ListItem, get_text_width};
+
+// where an item of the list is printed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemLayout {
+    pub index: usize,
+    pub row: u16,
+    // the number of rows it takes after wrapping
+    pub height: u16,
+}
+
+// the number of rows the text takes when the terminal
+// wraps it at max_width columns
+pub fn get_wrapped_height(text: &str, max_width: u16) -> u16 {
+    let max_width = std::cmp::max(max_width as usize, 1);
+    let height: usize = text.split('\n')
+        .map(|line| std::cmp::max(1, get_text_width(line).div_ceil(max_width)))
+        .sum();
+    height as u16
+}
+
+// the start of the text that fits in max_width columns, for
+// the rows that must not wrap, like the footer and the messages
+pub fn truncate_to_width(text: &str, max_width: u16) -> String {
+    let mut truncated = String::new();
+    let mut width = 0;
+    for grapheme in text.graphemes(true) {
+        width += get_text_width(grapheme);
+        if width > max_width as usize {
+            break;
+        }
+        truncated.push_str(grapheme);
+    }
+    truncated
+}
+
+// places the items from cursor_offset on, until the list area of
+// max_cursor rows is full. the heights are measured on the visible
+// text of the items, without the color escape sequences.
+pub fn get_list_layout(
+    out_vec: &[ListItem],
+    cursor_offset: usize,
+    cursor_position: i32,
+    max_cursor: u16,
+    max_width: u16,
+) -> Vec<ItemLayout> {
+    let mut layout = vec![];
+    let mut row = 0;
+
+    for (num, item) in out_vec.iter().enumerate().skip(cursor_offset) {
+        if row >= max_cursor {
+            break;
+        }
+
+        let is_highlighted = (num - cursor_offset) as i32 == cursor_position;
+        let height = get_wrapped_height(&item.get_visible_text(is_highlighted), max_width);
+        layout.push(ItemLayout { index: num, row, height });
+        row += height;
+    }
+
+    layout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::get_styles_from_yaml;
+    use crossterm::Color;
+    use yaml_rust::Yaml;
+
+    #[test]
+    fn get_list_layout_works() {
+        assert_eq!(get_wrapped_height("", 10), 1);
+        assert_eq!(get_wrapped_height("1234567890", 10), 1);
+        assert_eq!(get_wrapped_height("12345678901", 10), 2);
+        assert_eq!(get_wrapped_height("12345\n\u{4E16}\u{4E16}\u{4E16}\u{4E16}\u{4E16}\u{4E16}", 10), 3);
+        assert_eq!(truncate_to_width("12345", 3), "123");
+        assert_eq!(truncate_to_width("1\u{4E16}\u{4E16}", 4), "1\u{4E16}");
+        assert_eq!(truncate_to_width("12", 0), "");
+
+        let mut styles = get_styles_from_yaml(&Yaml::Null);
+        styles.1.color = Some(Color::Red);
+        styles.1.highlighted_color = Some(Color::Blue);
+        let question = "q".repeat(20);
+        let list = vec![
+            ListItem::from_styles(&question, "a", &styles),
+            ListItem::from_styles(&question, &"a".repeat(40), &styles),
+            ListItem::from_styles(&question, "a", &styles),
+        ];
+
+        // the escape sequences of the colors are not counted
+        let width = list[0].get_visible_text(false).chars().count() as u16;
+        assert!(list[0].get_output(false).len() > width as usize);
+        let layout = get_list_layout(&list, 0, 2, 100, width);
+        let rows: Vec<(u16, u16)> = layout.iter().map(|l| (l.row, l.height)).collect();
+        assert_eq!(rows[0], (0, 1));
+        assert_eq!(rows[1].0, 1);
+        assert!(rows[1].1 > 1);
+        assert_eq!(rows[2].0, 1 + rows[1].1);
+
+        // it stops when the rows are full
+        assert_eq!(get_list_layout(&list, 1, 0, 1, width).len(), 1);
+    }
+}
@@ -0,0 +1,145 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::Action;
+
+// the start of the word before the cursor. words are
+// alphanumeric, or anything but whitespace for ctrl-w.
+fn word_start(graphemes: &[&str], cursor: usize, is_word_char: fn(&str) -> bool) -> usize {
+    let mut index = cursor;
+    while index > 0 && !is_word_char(graphemes[index - 1]) {
+        index -= 1;
+    }
+    while index > 0 && is_word_char(graphemes[index - 1]) {
+        index -= 1;
+    }
+    index
+}
+
+// the end of the word after the cursor
+fn word_end(graphemes: &[&str], cursor: usize, is_word_char: fn(&str) -> bool) -> usize {
+    let mut index = cursor;
+    while index < graphemes.len() && !is_word_char(graphemes[index]) {
+        index += 1;
+    }
+    while index < graphemes.len() && is_word_char(graphemes[index]) {
+        index += 1;
+    }
+    index
+}
+
+fn is_alphanumeric(g: &str) -> bool {
+    g.chars().next().is_some_and(char::is_alphanumeric)
+}
+
+fn is_not_whitespace(g: &str) -> bool {
+    !g.chars().all(char::is_whitespace)
+}
+
+// applies a readline style action to the text. the cursor is the
+// number of grapheme clusters before the edit point, so an emoji
+// or an accented letter is moved over and deleted as one.
+// returns false if the action is not a line editing action.
+pub fn edit_line(text: &mut String, cursor: &mut usize, action: &Action) -> bool {
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let len = graphemes.len();
+    let position = (*cursor).min(len);
+
+    // the text with the graphemes in start..end replaced,
+    // and where the cursor ends up
+    let splice = |start: usize, end: usize, insert: &str| {
+        let mut before = graphemes[..start].concat();
+        before.push_str(insert);
+        // a combining char joins the grapheme before it
+        let position = before.graphemes(true).count();
+        before.push_str(&graphemes[end..].concat());
+        (before, position)
+    };
+
+    let (new_text, new_position) = match action {
+        // an answer is one line, without tabs or other control chars
+        Action::InsertChar(c) if c.is_control() => return true,
+        Action::InsertChar(c) => splice(position, position, &c.to_string()),
+        Action::Backspace if position > 0 => splice(position - 1, position, ""),
+        Action::Delete if position < len => splice(position, position + 1, ""),
+        Action::Backspace | Action::Delete => return true,
+        Action::DeleteToEnd => splice(position, len, ""),
+        Action::DeleteToStart => splice(0, position, ""),
+        Action::DeleteWordBack => {
+            splice(word_start(&graphemes, position, is_not_whitespace), position, "")
+        },
+        Action::CursorLeft => (text.clone(), position.saturating_sub(1)),
+        Action::CursorRight => (text.clone(), (position + 1).min(len)),
+        Action::CursorHome => (text.clone(), 0),
+        Action::CursorEnd => (text.clone(), len),
+        Action::WordLeft => (text.clone(), word_start(&graphemes, position, is_alphanumeric)),
+        Action::WordRight => (text.clone(), word_end(&graphemes, position, is_alphanumeric)),
+        _ => return false,
+    };
+
+    *text = new_text;
+    *cursor = new_position;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_line_works() {
+        let mut text = "hello world".to_string();
+        let mut cursor = 100;
+
+        let edit = |action, text: &mut String, cursor: &mut usize| {
+            assert!(edit_line(text, cursor, &action));
+        };
+
+        edit(Action::WordLeft, &mut text, &mut cursor);
+        assert_eq!(cursor, 6);
+        edit(Action::InsertChar('_'), &mut text, &mut cursor);
+        assert_eq!(text, "hello _world");
+        edit(Action::CursorLeft, &mut text, &mut cursor);
+        edit(Action::Backspace, &mut text, &mut cursor);
+        assert_eq!(text, "hello_world");
+        assert_eq!(cursor, 5);
+        edit(Action::Delete, &mut text, &mut cursor);
+        edit(Action::DeleteToEnd, &mut text, &mut cursor);
+        assert_eq!(text, "hello");
+        edit(Action::CursorHome, &mut text, &mut cursor);
+        edit(Action::WordRight, &mut text, &mut cursor);
+        assert_eq!(cursor, 5);
+        edit(Action::InsertChar(' '), &mut text, &mut cursor);
+        edit(Action::InsertChar('a'), &mut text, &mut cursor);
+        edit(Action::DeleteWordBack, &mut text, &mut cursor);
+        assert_eq!(text, "hello ");
+        edit(Action::CursorLeft, &mut text, &mut cursor);
+        edit(Action::DeleteToStart, &mut text, &mut cursor);
+        assert_eq!(text, " ");
+        assert_eq!(cursor, 0);
+
+        assert!(!edit_line(&mut text, &mut cursor, &Action::Submit));
+    }
+
+    #[test]
+    fn edit_line_uses_graphemes() {
+        // e + combining acute accent, a family emoji and a wide char
+        let mut text = "cafe\u{301} \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467} \u{4E16}".to_string();
+        let mut cursor = 100;
+
+        assert!(edit_line(&mut text, &mut cursor, &Action::CursorEnd));
+        assert_eq!(cursor, 8);
+        assert!(edit_line(&mut text, &mut cursor, &Action::Backspace));
+        assert!(edit_line(&mut text, &mut cursor, &Action::Backspace));
+        assert!(edit_line(&mut text, &mut cursor, &Action::Backspace));
+        assert_eq!(text, "cafe\u{301} ");
+        assert!(edit_line(&mut text, &mut cursor, &Action::CursorLeft));
+        assert!(edit_line(&mut text, &mut cursor, &Action::Backspace));
+        assert_eq!(text, "caf ");
+
+        // the accent joins the e that was typed before it
+        assert!(edit_line(&mut text, &mut cursor, &Action::InsertChar('e')));
+        assert!(edit_line(&mut text, &mut cursor, &Action::InsertChar('\u{301}')));
+        assert_eq!(text, "cafe\u{301} ");
+        assert_eq!(cursor, 4);
+    }
+}
@@ -0,0 +1,297 @@
+use crossterm::{InputEvent, KeyEvent};
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::super::utils::get_text_width;
+use super::{Backend, Events, InteractEvent, ScreenMode, Action};
+
+// an event that is sent to interact, or a new size of the screen
+#[derive(Debug, Clone)]
+enum ScriptInput {
+    Input(InputEvent),
+    Resize(u16, u16),
+}
+
+// a screen in memory, for running interact without a terminal.
+// the input is a list of events, and what was written
+// is kept in cells that tests can look at, eg:
+//
+// let mut backend = MemoryBackend::new(80, 24);
+// backend.add_keys(&[KeyEvent::Down, KeyEvent::Ctrl('g')]);
+// interact_with_options(&mut items, InteractOptions {
+//     backend: Some(&mut backend),
+//     ..InteractOptions::default()
+// })?;
+// assert_eq!(backend.get_lines()[0], "...");
+#[derive(Debug, Clone)]
+pub struct MemoryBackend {
+    width: u16,
+    height: u16,
+    // a grapheme per cell, the cell after a wide one is empty
+    cells: Vec<Vec<String>>,
+    cursor_x: u16,
+    cursor_y: u16,
+    cursor_visible: bool,
+    input: Vec<ScriptInput>,
+    // the size the screen gets when the resize event is handled
+    pending_size: Arc<Mutex<Option<(u16, u16)>>>,
+    // the screen every time it was flushed
+    frames: Vec<String>,
+}
+
+impl MemoryBackend {
+    pub fn new(width: u16, height: u16) -> MemoryBackend {
+        MemoryBackend {
+            width,
+            height,
+            cells: vec![get_empty_row(width); height as usize],
+            cursor_x: 0,
+            cursor_y: 0,
+            cursor_visible: true,
+            input: vec![],
+            pending_size: Arc::new(Mutex::new(None)),
+            frames: vec![],
+        }
+    }
+
+    pub fn add_input(&mut self, event: InputEvent) {
+        self.input.push(ScriptInput::Input(event));
+    }
+
+    // resizes the screen after the input added before this
+    pub fn add_resize(&mut self, width: u16, height: u16) {
+        self.input.push(ScriptInput::Resize(width, height));
+    }
+
+    pub fn add_keys(&mut self, keys: &[KeyEvent]) {
+        for key in keys {
+            self.add_input(InputEvent::Keyboard(key.clone()));
+        }
+    }
+
+    // like a terminal that was resized, the rows
+    // and columns that do not fit are cut off
+    pub fn set_size(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+        self.cells.resize(height as usize, get_empty_row(width));
+        for row in self.cells.iter_mut() {
+            row.resize(width as usize, " ".to_string());
+        }
+        self.cursor_x = self.cursor_x.min(width.saturating_sub(1));
+        self.cursor_y = self.cursor_y.min(height.saturating_sub(1));
+    }
+
+    // the text of every row, without the spaces at the end
+    pub fn get_lines(&self) -> Vec<String> {
+        self.cells.iter()
+            .map(|row| row.concat().trim_end().to_string())
+            .collect()
+    }
+
+    pub fn get_screen(&self) -> String {
+        self.get_lines().join("\n")
+    }
+
+    pub fn get_cursor(&self) -> (u16, u16) {
+        (self.cursor_x, self.cursor_y)
+    }
+
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    pub fn get_frames(&self) -> &[String] {
+        &self.frames
+    }
+
+    // the resize event sets the pending size when interact
+    // handles it, and the screen changes before the next output
+    fn apply_resize(&mut self) {
+        let pending_size = self.pending_size.lock().unwrap().take();
+        if let Some((width, height)) = pending_size {
+            self.set_size(width, height);
+        }
+    }
+
+    fn line_feed(&mut self) {
+        if self.cursor_y + 1 < self.height {
+            self.cursor_y += 1;
+        } else if self.height > 0 {
+            // scrolls the screen up
+            self.cells.remove(0);
+            self.cells.push(get_empty_row(self.width));
+        }
+    }
+
+    fn write_grapheme(&mut self, grapheme: &str) {
+        let width = get_text_width(grapheme) as u16;
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        if width == 0 {
+            // joins the grapheme before it
+            if self.cursor_x > 0 {
+                self.cells[self.cursor_y as usize][self.cursor_x as usize - 1].push_str(grapheme);
+            }
+            return;
+        }
+
+        // text that does not fit wraps into the next row
+        if self.cursor_x + width > self.width {
+            self.cursor_x = 0;
+            self.line_feed();
+        }
+
+        let row = &mut self.cells[self.cursor_y as usize];
+        let x = self.cursor_x as usize;
+        row[x] = grapheme.to_string();
+        for cell in row.iter_mut().skip(x + 1).take(width as usize - 1) {
+            cell.clear();
+        }
+        self.cursor_x += width;
+    }
+}
+
+fn get_empty_row(width: u16) -> Vec<String> {
+    vec![" ".to_string(); width as usize]
+}
+
+// the text without the escape codes of the styles
+fn strip_escape_codes(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        // ESC [ params final, or ESC and one char
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+impl Backend for MemoryBackend {
+    fn size(&self) -> (u16, u16) {
+        self.pending_size.lock().unwrap().unwrap_or((self.width, self.height))
+    }
+
+    fn cursor_pos(&mut self) -> (u16, u16) {
+        self.apply_resize();
+        self.get_cursor()
+    }
+
+    fn goto(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.apply_resize();
+        self.cursor_x = x.min(self.width.saturating_sub(1));
+        self.cursor_y = y.min(self.height.saturating_sub(1));
+        Ok(())
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.cursor_visible = true;
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.cursor_visible = false;
+        Ok(())
+    }
+
+    fn clear_all(&mut self) -> io::Result<()> {
+        self.apply_resize();
+        self.cells = vec![get_empty_row(self.width); self.height as usize];
+        self.goto(0, 0)
+    }
+
+    fn clear_line(&mut self) -> io::Result<()> {
+        self.apply_resize();
+        if let Some(row) = self.cells.get_mut(self.cursor_y as usize) {
+            *row = get_empty_row(self.width);
+        }
+        Ok(())
+    }
+
+    // a new line also goes back to the first column,
+    // like the output of a terminal that is not raw
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.apply_resize();
+        let text = strip_escape_codes(text);
+        for grapheme in text.graphemes(true) {
+            match grapheme {
+                "\n" | "\r\n" => {
+                    self.cursor_x = 0;
+                    self.line_feed();
+                },
+                "\r" => self.cursor_x = 0,
+                _ => self.write_grapheme(grapheme),
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.apply_resize();
+        self.frames.push(self.get_screen());
+        Ok(())
+    }
+
+    // sends all of the input at once. interact is cancelled
+    // when it runs out, so it never waits for more.
+    fn read_input(&mut self, events: &Events) {
+        let sender = events.sender();
+        for input in self.input.drain(..) {
+            let event = match input {
+                ScriptInput::Input(event) => InteractEvent::Input(event),
+                ScriptInput::Resize(width, height) => {
+                    let pending_size = self.pending_size.clone();
+                    InteractEvent::Update(Box::new(move |_| {
+                        *pending_size.lock().unwrap() = Some((width, height));
+                    }))
+                },
+            };
+            let _ = sender.send(event);
+        }
+        let _ = sender.send(InteractEvent::Action(Action::Cancel));
+    }
+
+    fn enter(&mut self, _mode: ScreenMode) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn leave(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_backend_works() {
+        let mut backend = MemoryBackend::new(6, 3);
+        backend.write("\x1b[38;5;1mab\x1b[0mc").unwrap();
+        backend.goto(1, 1).unwrap();
+        backend.write("\u{4E16}defgh").unwrap();
+        assert_eq!(backend.get_lines(), vec!["abc", " \u{4E16}def", "gh"]);
+        assert_eq!(backend.get_cursor(), (2, 2));
+
+        backend.goto(0, 0).unwrap();
+        backend.clear_line().unwrap();
+        backend.write("\n\n\nx").unwrap();
+        assert_eq!(backend.get_screen(), " \u{4E16}def\ngh\nx");
+
+        backend.set_size(2, 2);
+        assert_eq!(backend.get_lines(), vec![" \u{4E16}", "gh"]);
+    }
+}
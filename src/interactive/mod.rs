@@ -1,9 +1,66 @@
 mod interact;
 mod print_list;
+mod layout;
+mod render;
+mod print_help;
+mod choices;
+mod values;
 mod events;
+mod keymap;
+mod edit;
+mod line_edit;
+mod guard;
+mod backend;
+mod memory_backend;
+mod harness;
+mod prompt;
 
 pub use interact::interact;
+pub use interact::interact_with_options;
+pub use interact::InteractOptions;
+pub use interact::ActionHandler;
+pub use interact::ScreenMode;
+pub use interact::get_screen_mode_from_yaml;
+pub use interact::InteractResult;
+pub use interact::get_changed_items;
 pub use print_list::print_list;
-pub use events::KeyCharPressed;
-pub use events::down_or_up;
-pub use events::get_key_char;
+pub use layout::ItemLayout;
+pub use layout::get_wrapped_height;
+pub use layout::truncate_to_width;
+pub use layout::get_list_layout;
+pub use print_help::print_help;
+pub use print_help::get_help_lines;
+pub use print_help::get_edit_help_lines;
+pub use choices::has_choices;
+pub use choices::cycle_choice;
+pub use choices::jump_to_choice;
+pub use values::select_value;
+pub use values::add_value;
+pub use values::remove_value;
+pub use values::move_value;
+pub use events::get_action;
+pub use events::InteractEvent;
+pub use events::Events;
+pub use events::UpdateItems;
+pub use keymap::Keymap;
+pub use keymap::Action;
+pub use keymap::parse_key;
+pub use keymap::get_key_name;
+pub use keymap::get_keymap_from_yaml;
+pub use edit::edit_list_item;
+pub use line_edit::edit_line;
+pub use guard::TerminalGuard;
+pub use backend::Backend;
+pub use backend::CrosstermBackend;
+pub use memory_backend::MemoryBackend;
+pub use harness::ScriptResult;
+pub use harness::run_script;
+pub use prompt::Fallback;
+pub use prompt::get_fallback_from_yaml;
+pub use prompt::prompt_list_items;
+pub use render::Frame;
+pub use render::FrameLine;
+pub use render::Renderer;
+pub use render::add_list_to_frame;
+pub use render::add_help_to_frame;
+pub use render::get_changed_rows;
@@ -0,0 +1,227 @@
+use std::io;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::super::utils::{ListItem, get_text_width};
+use super::{add_help_to_frame, Backend, Frame, Renderer};
+use super::{has_choices, Action, Keymap};
+
+// splits the text into lines that fit in max_width columns,
+// without splitting grapheme clusters
+fn wrap_text(text: &str, max_width: u16, out_lines: &mut Vec<String>) {
+    let max_width = std::cmp::max(max_width as usize, 1);
+    for line in text.lines() {
+        let mut current = String::new();
+        let mut current_width = 0;
+        for grapheme in line.graphemes(true) {
+            let width = get_text_width(grapheme);
+            if current_width + width > max_width && !current.is_empty() {
+                out_lines.push(std::mem::take(&mut current));
+                current_width = 0;
+            }
+            current.push_str(grapheme);
+            current_width += width;
+        }
+        out_lines.push(current);
+    }
+}
+
+pub fn get_help_lines(item: &ListItem, max_width: u16) -> Vec<String> {
+    let mut lines = vec![];
+    let info = &item.info;
+
+    // prefer the long help, since there is a pane for it
+    if let Some(help) = info.long_help.as_ref().or(info.help.as_ref()) {
+        wrap_text(help, max_width, &mut lines);
+    }
+
+    if !info.possible_values.is_empty() {
+        // mark the current answer so the user
+        // can see where they are when cycling
+        let choices: Vec<String> = info.possible_values.iter().map(|v| {
+            if v == item.get_edit_text() {
+                format!("[{}]", v)
+            } else {
+                v.clone()
+            }
+        }).collect();
+        wrap_text(&format!("choices: {}", choices.join(" | ")), max_width, &mut lines);
+    }
+
+    if item.is_multiple() {
+        let values: Vec<String> = item.values.iter().enumerate().map(|(i, v)| {
+            if i == item.value_index {
+                format!("[{}]", v)
+            } else {
+                v.clone()
+            }
+        }).collect();
+        wrap_text(&format!("values: {}", values.join(" | ")), max_width, &mut lines);
+    }
+
+    let mut details = vec![];
+    if let Some(value_name) = &info.value_name {
+        details.push(format!("value: {}", value_name));
+    }
+    if let Some(env) = &info.env {
+        details.push(format!("env: {}", env));
+    }
+    if let Some(default_value) = &info.default_value {
+        details.push(format!("default: {}", default_value));
+    }
+    if !details.is_empty() {
+        wrap_text(&details.join(" | "), max_width, &mut lines);
+    }
+
+    if lines.is_empty() {
+        lines.push("no help available".to_string());
+    }
+
+    lines
+}
+
+// the keys that edit the item, besides the ones of the footer
+fn get_edit_keys(item: &ListItem, keymap: &Keymap) -> Option<String> {
+    let key = |action| keymap.describe_any(&[action], true);
+    let choose = format!("{}/{} or type to choose", key(Action::MoveUp), key(Action::MoveDown));
+    let values = format!(
+        "{} to add, {} to remove, {}/{} to move",
+        key(Action::NewValue),
+        key(Action::DeleteValue),
+        key(Action::MoveValueUp),
+        key(Action::MoveValueDown),
+    );
+
+    let keys = if item.is_multiple() && has_choices(item) {
+        format!("{}, {}/{} to select, {}", choose, key(Action::CursorLeft), key(Action::CursorRight), values)
+    } else if item.is_multiple() {
+        format!("{}/{} to select, {}", key(Action::MoveUp), key(Action::MoveDown), values)
+    } else if item.info.is_flag {
+        // these are typed chars, not keys of the keymap
+        format!("Space/+ to set, -/{} to unset", key(Action::Backspace))
+    } else if has_choices(item) {
+        choose
+    } else {
+        return None;
+    };
+    Some(format!("keys: {}", keys))
+}
+
+// while editing, the keys that edit the item come before its help
+pub fn get_edit_help_lines(item: &ListItem, keymap: &Keymap, max_width: u16) -> Vec<String> {
+    let mut lines = vec![];
+    if let Some(keys) = get_edit_keys(item, keymap) {
+        wrap_text(&keys, max_width, &mut lines);
+    }
+    lines.extend(get_help_lines(item, max_width));
+    lines
+}
+
+// prints the help pane of the item from start_row down, like
+// add_help_to_frame does for the frames interact renders
+pub fn print_help(
+    backend: &mut dyn Backend,
+    start_row: u16,
+    help_height: u16,
+    max_width: u16,
+    item: Option<&ListItem>,
+) -> io::Result<()> {
+    let mut frame = Frame::new(help_height);
+    add_help_to_frame(&mut frame, 0, help_height, max_width, item, None);
+    Renderer::inline(start_row).render(backend, frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::get_styles_from_yaml;
+    use super::super::FrameLine;
+    use crossterm::KeyEvent;
+    use yaml_rust::Yaml;
+
+    fn get_item(answer: &str) -> ListItem {
+        ListItem::from_styles("q", answer, &get_styles_from_yaml(&Yaml::Null))
+    }
+
+    #[test]
+    fn get_help_lines_works() {
+        let mut item = get_item("b");
+        assert_eq!(get_help_lines(&item, 20), vec!["no help available"]);
+
+        // the long help is preferred, and wrapped without splitting wide chars
+        item.info.help = Some("short".to_string());
+        assert_eq!(get_help_lines(&item, 20), vec!["short"]);
+        item.info.long_help = Some("a long help\n\u{4E16}\u{4E16}\u{4E16}".to_string());
+        assert_eq!(get_help_lines(&item, 5), vec!["a lon", "g hel", "p", "\u{4E16}\u{4E16}", "\u{4E16}"]);
+
+        item.info.long_help = None;
+        item.info.possible_values = vec!["a".to_string(), "b".to_string()];
+        item.info.value_name = Some("NAME".to_string());
+        item.info.env = Some("MY_ENV".to_string());
+        item.info.default_value = Some("a".to_string());
+        assert_eq!(get_help_lines(&item, 80), vec![
+            "short",
+            "choices: a | [b]",
+            "value: NAME | env: MY_ENV | default: a",
+        ]);
+
+        // the selected value is marked
+        let mut item = get_item("");
+        item.info.multiple = true;
+        item.values = vec!["x".to_string(), "y".to_string()];
+        item.value_index = 1;
+        item.info.default_value = Some("x".to_string());
+        assert_eq!(get_help_lines(&item, 80), vec!["values: x | [y]", "default: x"]);
+    }
+
+    #[test]
+    fn get_edit_help_lines_works() {
+        let mut keymap = Keymap::default();
+        let mut item = get_item("");
+        item.info.help = Some("help".to_string());
+        assert_eq!(get_edit_help_lines(&item, &keymap, 80), vec!["help"]);
+
+        // the keys come from the keymap
+        keymap.bind(Action::NewValue, vec![vec![KeyEvent::Ctrl('o')]]);
+        item.info.multiple = true;
+        item.values = vec!["x".to_string()];
+        assert_eq!(get_edit_help_lines(&item, &keymap, 100), vec![
+            "keys: Up/Down to select, CTRL-o to add, CTRL-d to remove, CTRL-Up/CTRL-Down to move",
+            "help",
+            "values: [x]",
+        ]);
+
+        item.info.possible_values = vec!["x".to_string(), "y".to_string()];
+        let lines = get_edit_help_lines(&item, &Keymap::vim(), 200);
+        assert!(lines[0].starts_with("keys: Up/Down or type to choose, Left/Right to select"));
+
+        let mut item = get_item("");
+        item.info.is_flag = true;
+        let lines = get_edit_help_lines(&item, &Keymap::default(), 80);
+        assert_eq!(lines[0], "keys: Space/+ to set, -/Backspace to unset");
+    }
+
+    #[test]
+    fn add_help_to_frame_works() {
+        let mut item = get_item("b");
+        item.info.help = Some("1\n2\n3\n4\n5".to_string());
+
+        // the separator, then as many lines as fit
+        let mut frame = Frame::new(5);
+        add_help_to_frame(&mut frame, 1, 3, 4, Some(&item), None);
+        let lines: Vec<FrameLine> = ["", "----", "1", "2", ""].iter()
+            .map(|line| FrameLine::Text(line.to_string(), 1))
+            .collect();
+        assert_eq!(frame.lines, lines);
+
+        let mut frame = Frame::new(2);
+        add_help_to_frame(&mut frame, 0, 2, 3, None, None);
+        assert_eq!(frame.lines[0], FrameLine::Text("---".to_string(), 1));
+        assert_eq!(frame.lines[1], FrameLine::Text("".to_string(), 1));
+
+        // without rows for it, there is no help pane
+        let mut frame = Frame::new(2);
+        add_help_to_frame(&mut frame, 0, 0, 3, Some(&item), None);
+        assert_eq!(frame, Frame::new(2));
+    }
+}
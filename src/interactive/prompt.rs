@@ -0,0 +1,156 @@
+use std::io::{self, BufRead, Write};
+use yaml_rust::Yaml;
+
+use super::super::utils::{ListItem, all_list_items_valid};
+use super::{InteractResult, get_changed_items};
+
+// what interact does when there is no terminal to interact
+// in, eg: in CI, or when the input or output is piped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fallback {
+    // asks the questions a line at a time, see prompt_list_items
+    Prompt,
+    // returns the error of the terminal
+    Error,
+}
+
+// interactive_fallback: prompt | error
+pub fn get_fallback_from_yaml(yaml_obj: &Yaml) -> Fallback {
+    match yaml_obj["interactive_fallback"].as_str() {
+        Some("error") => Fallback::Error,
+        _ => Fallback::Prompt,
+    }
+}
+
+// the line that asks for the answer of the item, eg:
+// "color (red|blue) [red]: "
+fn get_prompt(item: &ListItem) -> String {
+    let mut prompt = item.question.clone();
+    if !item.info.possible_values.is_empty() {
+        prompt.push_str(&format!(" ({})", item.info.possible_values.join("|")));
+    }
+
+    // flags are answered with true/false, or a count
+    let answer = if item.info.is_flag {
+        item.answer.clone()
+    } else {
+        item.get_display_answer()
+    };
+    if !answer.is_empty() {
+        prompt.push_str(&format!(" [{}]", answer));
+    }
+
+    prompt.push_str(": ");
+    prompt
+}
+
+// sets the answer of the item from a line that was typed.
+// returns false if it is not an answer for a flag.
+fn set_answer_from_line(item: &mut ListItem, line: &str) -> bool {
+    if item.info.is_flag {
+        let count = match line {
+            "y" | "yes" | "true" => 1,
+            "n" | "no" | "false" => 0,
+            count => match count.parse() {
+                Ok(count) => count,
+                Err(_) => return false,
+            },
+        };
+        item.set_flag_count(count);
+    } else if item.is_multiple() {
+        item.values = line.split(',')
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect();
+        item.value_index = 0;
+    } else {
+        item.answer = line.to_string();
+    }
+
+    item.move_edit_cursor_to_end();
+    item.validate();
+    item.update_output();
+    true
+}
+
+// asks the question of every item, one after the other. an
+// empty line keeps the answer, and invalid answers are asked
+// again. when the input ends, the rest of the answers are kept.
+pub fn prompt_list_items(
+    out_vec: &mut [ListItem],
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> io::Result<InteractResult> {
+    let original_items = out_vec.to_vec();
+    let mut input_ended = false;
+
+    for item in out_vec.iter_mut() {
+        item.validate();
+        while !input_ended {
+            write!(output, "{}", get_prompt(item))?;
+            output.flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output)?;
+                input_ended = true;
+                break;
+            }
+
+            let line = line.trim_end_matches(['\r', '\n']);
+            if !line.is_empty() && !set_answer_from_line(item, line) {
+                writeln!(output, "  answer with y/n, or a number")?;
+                continue;
+            }
+            match &item.error {
+                Some(error) => writeln!(output, "  {}", error)?,
+                None => break,
+            }
+        }
+    }
+
+    if !all_list_items_valid(out_vec) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "some answers are invalid",
+        ));
+    }
+
+    Ok(InteractResult::Submitted(get_changed_items(&original_items, out_vec)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::get_styles_from_yaml;
+
+    #[test]
+    fn prompt_list_items_works() {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut items = vec![
+            ListItem::from_styles("name", "bob", &styles),
+            ListItem::from_styles("color", "red", &styles),
+            ListItem::from_styles("verbose", "false", &styles),
+            ListItem::from_styles("other", "", &styles),
+        ];
+        items[1].info.possible_values = vec!["red".to_string(), "blue".to_string()];
+        items[2].info.is_flag = true;
+
+        let mut input = io::Cursor::new("\ngreen\nblue\nmaybe\ny\n");
+        let mut output = vec![];
+        let result = prompt_list_items(&mut items, &mut input, &mut output).unwrap();
+
+        assert_eq!(result, InteractResult::Submitted(vec![1, 2]));
+        assert_eq!(items[0].answer, "bob");
+        assert_eq!(items[1].answer, "blue");
+        assert_eq!(items[2].answer, "true");
+        assert_eq!(String::from_utf8(output).unwrap(), [
+            "name [bob]: ",
+            "color (red|blue) [red]:   must be one of: red, blue\n",
+            "color (red|blue) [green]: ",
+            "verbose [false]:   answer with y/n, or a number\n",
+            "verbose [false]: ",
+            "other: \n",
+        ].concat());
+    }
+}
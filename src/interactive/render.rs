@@ -0,0 +1,252 @@
+use std::io;
+
+use super::super::utils::ListItem;
+use super::{get_list_layout, get_help_lines, get_edit_help_lines, Backend, Keymap};
+
+// one row of the screen. text that wraps takes the rows below it,
+// and these are Continued.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameLine {
+    // the styled text, and the number of rows it takes
+    Text(String, u16),
+    Continued,
+}
+
+// everything that is shown on the screen, row by row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub lines: Vec<FrameLine>,
+}
+
+impl Frame {
+    // a frame of empty rows
+    pub fn new(height: u16) -> Frame {
+        Frame {
+            lines: vec![FrameLine::Text("".to_string(), 1); height as usize],
+        }
+    }
+
+    // sets the text of the row, and marks the rows it wraps into
+    pub fn set_line(&mut self, row: u16, text: &str, height: u16) {
+        let row = row as usize;
+        if row >= self.lines.len() {
+            return;
+        }
+
+        // the rows the previous text wrapped into are empty now
+        if let FrameLine::Text(_, previous_height) = self.lines[row] {
+            for line in self.lines.iter_mut().skip(row + 1).take(previous_height as usize - 1) {
+                if *line == FrameLine::Continued {
+                    *line = FrameLine::Text("".to_string(), 1);
+                }
+            }
+        }
+
+        let height = std::cmp::max(height, 1);
+        self.lines[row] = FrameLine::Text(text.to_string(), height);
+        for line in self.lines.iter_mut().skip(row + 1).take(height as usize - 1) {
+            *line = FrameLine::Continued;
+        }
+    }
+}
+
+// adds the items that fit in max_cursor rows to the frame, and
+// returns the last row of the highlighted item
+pub fn add_list_to_frame(
+    frame: &mut Frame,
+    max_cursor: u16,
+    max_width: u16,
+    out_vec: &[ListItem],
+    cursor_offset: usize,
+    cursor_position: i32,
+) -> u16 {
+    let mut highlighted_position = 0;
+    let layout = get_list_layout(out_vec, cursor_offset, cursor_position, max_cursor, max_width);
+
+    for item_layout in layout.iter() {
+        let is_highlighted = (item_layout.index - cursor_offset) as i32 == cursor_position;
+        let output = out_vec[item_layout.index].get_output(is_highlighted);
+        frame.set_line(item_layout.row, output, item_layout.height);
+
+        if is_highlighted {
+            highlighted_position = item_layout.row + item_layout.height - 1;
+        }
+    }
+
+    highlighted_position
+}
+
+// adds the help pane to the frame. the keymap is
+// given while the item is edited, to show its keys.
+pub fn add_help_to_frame(
+    frame: &mut Frame,
+    start_row: u16,
+    help_height: u16,
+    max_width: u16,
+    item: Option<&ListItem>,
+    keymap: Option<&Keymap>,
+) {
+    if help_height == 0 {
+        return;
+    }
+
+    frame.set_line(start_row, &"-".repeat(max_width as usize), 1);
+
+    if let Some(item) = item {
+        let help_lines = match keymap {
+            Some(keymap) => get_edit_help_lines(item, keymap, max_width),
+            None => get_help_lines(item, max_width),
+        };
+        for (num, line) in help_lines.iter().take(help_height as usize - 1).enumerate() {
+            frame.set_line(start_row + 1 + num as u16, line, 1);
+        }
+    }
+}
+
+// the rows that have to be printed again to go from the previous
+// frame to the next one: the ones that changed, and the rows of the
+// text they wrap into.
+pub fn get_changed_rows(previous: &Frame, next: &Frame) -> Vec<usize> {
+    let len = next.lines.len();
+    let mut dirty: Vec<bool> = (0..len)
+        .map(|row| previous.lines.get(row) != Some(&next.lines[row]))
+        .collect();
+
+    // a changed row that is wrapped into changes the text it belongs to
+    for row in 0..len {
+        if dirty[row] && next.lines[row] == FrameLine::Continued {
+            if let Some(head) = (0..row).rev().find(|r| next.lines[*r] != FrameLine::Continued) {
+                dirty[head] = true;
+            }
+        }
+    }
+
+    let mut changed = vec![];
+    let mut forced_until = 0;
+    for (row, line) in next.lines.iter().enumerate() {
+        if let FrameLine::Text(_, height) = line {
+            if dirty[row] || row < forced_until {
+                changed.push(row);
+                // printing it again clears the rows below, so
+                // whatever was there has to be printed again too
+                forced_until = std::cmp::max(forced_until, row + *height as usize);
+            }
+        }
+    }
+
+    changed
+}
+
+// keeps the frame that is on the screen, and only
+// prints the rows that are different in the next one.
+// the frame is printed from the origin row down, which is
+// 0 for the full screen, and the row under the cursor inline.
+#[derive(Debug, Clone, Default)]
+pub struct Renderer {
+    previous: Option<Frame>,
+    origin: u16,
+    inline: bool,
+}
+
+impl Renderer {
+    pub fn new() -> Renderer {
+        Renderer::default()
+    }
+
+    // a renderer that only uses the rows of its
+    // frames, and never clears the whole screen
+    pub fn inline(origin: u16) -> Renderer {
+        Renderer {
+            previous: None,
+            origin,
+            inline: true,
+        }
+    }
+
+    pub fn get_origin(&self) -> u16 {
+        self.origin
+    }
+
+    pub fn set_origin(&mut self, origin: u16) {
+        self.origin = origin;
+        self.invalidate();
+    }
+
+    // the next render prints every row, eg: after the
+    // screen was cleared or resized
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    pub fn render(&mut self, backend: &mut dyn Backend, frame: Frame) -> io::Result<()> {
+        let rows = match &self.previous {
+            Some(previous) => get_changed_rows(previous, &frame),
+            None => {
+                if !self.inline {
+                    backend.clear_all()?;
+                }
+                get_changed_rows(&Frame { lines: vec![] }, &frame)
+            },
+        };
+
+        for row in rows {
+            if let FrameLine::Text(text, height) = &frame.lines[row] {
+                for clear_row in row..std::cmp::min(row + *height as usize, frame.lines.len()) {
+                    backend.goto(0, self.origin + clear_row as u16)?;
+                    backend.clear_line()?;
+                }
+                backend.goto(0, self.origin + row as u16)?;
+                backend.write(text)?;
+            }
+        }
+        backend.flush()?;
+
+        self.previous = Some(frame);
+        Ok(())
+    }
+
+    // clears the rows of the last frame, and
+    // moves the cursor to the origin
+    pub fn clear(&mut self, backend: &mut dyn Backend) -> io::Result<()> {
+        if let Some(previous) = self.previous.take() {
+            for row in 0..previous.lines.len() {
+                backend.goto(0, self.origin + row as u16)?;
+                backend.clear_line()?;
+            }
+        }
+        backend.goto(0, self.origin)?;
+        backend.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_changed_rows_works() {
+        let mut previous = Frame::new(6);
+        previous.set_line(0, "a", 1);
+        previous.set_line(1, "b", 2);
+        previous.set_line(3, "c", 1);
+
+        let mut next = previous.clone();
+        assert!(get_changed_rows(&previous, &next).is_empty());
+
+        next.set_line(3, "changed", 1);
+        assert_eq!(get_changed_rows(&previous, &next), vec![3]);
+
+        // b wraps into one row less, so the row it leaves is cleared
+        let mut next = previous.clone();
+        next.set_line(1, "b", 1);
+        assert_eq!(get_changed_rows(&previous, &next), vec![1, 2]);
+
+        // a longer b wraps over the row of c
+        let mut next = previous.clone();
+        next.set_line(1, "b", 3);
+        next.set_line(4, "d", 1);
+        assert_eq!(get_changed_rows(&previous, &next), vec![1, 4]);
+
+        assert_eq!(get_changed_rows(&Frame { lines: vec![] }, &previous), vec![0, 1, 3, 4, 5]);
+    }
+}
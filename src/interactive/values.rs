@@ -0,0 +1,90 @@
+use super::super::utils::ListItem;
+
+// selects the next (direction 1) or previous (direction -1) value
+// of an arg that takes multiple values. stops at both ends.
+pub fn select_value(item: &mut ListItem, direction: i32) {
+    let next_index = item.value_index as i32 + direction;
+    if next_index >= 0 && (next_index as usize) < item.values.len() {
+        item.value_index = next_index as usize;
+    }
+}
+
+// adds an empty value after the selected one, and selects it
+pub fn add_value(item: &mut ListItem) {
+    let index = if item.values.is_empty() {
+        0
+    } else {
+        std::cmp::min(item.value_index + 1, item.values.len())
+    };
+    item.values.insert(index, "".to_string());
+    item.value_index = index;
+    item.update_output();
+}
+
+pub fn remove_value(item: &mut ListItem) {
+    if item.value_index < item.values.len() {
+        item.values.remove(item.value_index);
+    }
+    if item.value_index > 0 && item.value_index >= item.values.len() {
+        item.value_index -= 1;
+    }
+    item.update_output();
+}
+
+// swaps the selected value with the one before or after it,
+// the moved value stays selected
+pub fn move_value(item: &mut ListItem, direction: i32) {
+    let next_index = item.value_index as i32 + direction;
+    if next_index >= 0 && (next_index as usize) < item.values.len() {
+        item.values.swap(item.value_index, next_index as usize);
+        item.value_index = next_index as usize;
+        item.update_output();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::get_styles_from_yaml;
+    use yaml_rust::Yaml;
+
+    #[test]
+    fn editing_values_works() {
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut item = ListItem::from_styles("include", "", &styles);
+        item.info.multiple = true;
+
+        add_value(&mut item);
+        item.get_edit_text_mut().push('a');
+        add_value(&mut item);
+        item.get_edit_text_mut().push('b');
+        add_value(&mut item);
+        item.get_edit_text_mut().push('c');
+        assert_eq!(item.values, vec!["a", "b", "c"]);
+        assert_eq!(item.value_index, 2);
+
+        move_value(&mut item, -1);
+        assert_eq!(item.values, vec!["a", "c", "b"]);
+        assert_eq!(item.value_index, 1);
+
+        select_value(&mut item, -1);
+        select_value(&mut item, -1);
+        assert_eq!(item.value_index, 0);
+        move_value(&mut item, -1);
+        assert_eq!(item.values, vec!["a", "c", "b"]);
+
+        // new values go after the selected one
+        add_value(&mut item);
+        assert_eq!(item.values, vec!["a", "", "c", "b"]);
+        remove_value(&mut item);
+        assert_eq!(item.values, vec!["a", "c", "b"]);
+        assert_eq!(item.value_index, 1);
+
+        select_value(&mut item, 5);
+        assert_eq!(item.value_index, 1);
+        item.value_index = 2;
+        remove_value(&mut item);
+        assert_eq!(item.value_index, 1);
+        assert_eq!(item.get_display_answer(), "a, c");
+    }
+}
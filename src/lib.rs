@@ -1,5 +1,8 @@
 pub mod styles;
+pub mod questions;
+pub mod validation;
 pub mod utils;
+pub mod answers;
 pub mod interactive;
 
 
@@ -0,0 +1,403 @@
+use yaml_rust::{Yaml};
+use regex::Regex;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct InteractiveQuestion {
+  pub name: String,
+  // None keeps clap's default for args, or an empty
+  // answer for questions that are not args.
+  pub default: Option<String>,
+  // the yaml of the question, used for per-question styles
+  pub yaml: Yaml,
+}
+
+// scalar yaml values like `port: 8080` or `enabled: true`
+// are parsed as integers/bools, but answers are always strings.
+pub fn get_string_from_yaml(yaml_obj: &Yaml) -> Option<String> {
+  match yaml_obj {
+    Yaml::String(s) => Some(s.clone()),
+    Yaml::Real(s) => Some(s.clone()),
+    Yaml::Integer(i) => Some(i.to_string()),
+    Yaml::Boolean(b) => Some(b.to_string()),
+    _ => None,
+  }
+}
+
+pub fn parse_yaml_question(name: &str, yaml_obj: &Yaml) -> InteractiveQuestion {
+  let mut default = None;
+
+  if let Some(d) = get_string_from_yaml(yaml_obj) {
+    // - something else: 'the default'
+    default = Some(d);
+  } else if let Some(d) = get_string_from_yaml(&yaml_obj["default"]) {
+    // - verbose:
+    //     default: 'the default'
+    default = Some(d);
+  }
+
+  InteractiveQuestion {
+    name: name.to_string(),
+    default,
+    yaml: yaml_obj.clone(),
+  }
+}
+
+pub fn get_interactive_questions_from_yaml(yaml_obj: &Yaml) -> Vec<InteractiveQuestion> {
+  let mut questions = vec![];
+
+  if let Some(v) = yaml_obj["interactive_questions"].as_vec() {
+    for item in v {
+      if let Some(name) = item.as_str() {
+        // - question with no default
+        questions.push(parse_yaml_question(name, &Yaml::Null));
+      } else if let Some(h) = item.as_hash() {
+        for (key, value) in h {
+          if let Some(key_str) = get_string_from_yaml(key) {
+            questions.push(parse_yaml_question(&key_str, value));
+          }
+        }
+      }
+    }
+  }
+
+  questions
+}
+
+// the parts of a clap arg definition that are
+// useful to show to the user in interactive mode
+#[derive(Debug, Clone, Default)]
+pub struct ArgInfo {
+  pub help: Option<String>,
+  pub long_help: Option<String>,
+  pub value_name: Option<String>,
+  pub env: Option<String>,
+  pub default_value: Option<String>,
+  // when not empty, the answer must be one of these
+  pub possible_values: Vec<String>,
+  // clap args that do not take a value. the answer of a flag
+  // is "true"/"false", or the number of occurrences if multiple
+  pub is_flag: bool,
+  pub multiple: bool,
+}
+
+// the fields missing from the yaml are taken from the base info
+pub fn merge_yaml_arg_info(base_info: &ArgInfo, yaml_obj: &Yaml) -> ArgInfo {
+  let mut info = base_info.clone();
+
+  if let Some(h) = get_string_from_yaml(&yaml_obj["help"]) {
+    info.help = Some(h);
+  }
+  if let Some(lh) = get_string_from_yaml(&yaml_obj["long_help"]) {
+    info.long_help = Some(lh);
+  }
+  if let Some(vn) = get_string_from_yaml(&yaml_obj["value_name"]) {
+    info.value_name = Some(vn);
+  }
+  if let Some(e) = get_string_from_yaml(&yaml_obj["env"]) {
+    info.env = Some(e);
+  }
+  if let Some(d) = get_string_from_yaml(&yaml_obj["default_value"]) {
+    info.default_value = Some(d);
+  }
+  if let Some(v) = yaml_obj["possible_values"].as_vec() {
+    info.possible_values = v.iter().filter_map(get_string_from_yaml).collect();
+  } else if let Some(pv) = get_string_from_yaml(&yaml_obj["possible_values"]) {
+    // clap also allows a single value instead of a list
+    info.possible_values = vec![pv];
+  }
+  if let Some(m) = yaml_obj["multiple"].as_bool() {
+    info.multiple = m;
+  }
+
+  info
+}
+
+// clap treats an arg as taking a value if any of
+// these are set, otherwise it is a flag.
+pub fn is_yaml_flag(yaml_obj: &Yaml) -> bool {
+  let value_keys = [
+    "index",
+    "value_name",
+    "value_names",
+    "possible_values",
+    "default_value",
+    "default_value_if",
+    "default_value_ifs",
+    "number_of_values",
+    "min_values",
+    "max_values",
+    "value_delimiter",
+    "require_delimiter",
+    "use_delimiter",
+    "env",
+  ];
+
+  if let Some(tv) = yaml_obj["takes_value"].as_bool() {
+    return !tv;
+  }
+
+  !value_keys.iter().any(|k| !yaml_obj[*k].is_badvalue())
+}
+
+// reads the arg definitions from the clap args list. the
+// interactive_questions can add to, or override these fields.
+pub fn get_arg_infos_from_yaml(yaml_obj: &Yaml) -> HashMap<String, ArgInfo> {
+  let mut infos = HashMap::new();
+
+  // (section, is the section the clap args)
+  let sections = [
+    (&yaml_obj["args"], true),
+    (&yaml_obj["interactive_questions"], false),
+  ];
+  for (section, is_args) in sections.iter() {
+    if let Some(v) = section.as_vec() {
+      for item in v {
+        if let Some(h) = item.as_hash() {
+          for (key, value) in h {
+            if let Some(key_str) = get_string_from_yaml(key) {
+              let base_info = infos.remove(&key_str).unwrap_or_default();
+              let mut info = merge_yaml_arg_info(&base_info, value);
+              if *is_args {
+                // only clap args can be flags
+                info.is_flag = is_yaml_flag(value);
+              }
+              infos.insert(key_str, info);
+            }
+          }
+        }
+      }
+    }
+  }
+
+  infos
+}
+
+// patterns in interactive_exclude / interactive_include:
+//   /regex/   a regular expression
+//   glob*     a glob, supports * and ?
+//   name      an exact arg name
+#[derive(Debug, Clone)]
+pub enum QuestionPattern {
+  Exact(String),
+  Glob(String),
+  Regex(Regex),
+}
+
+impl QuestionPattern {
+  pub fn parse(pattern: &str) -> Result<QuestionPattern, regex::Error> {
+    if pattern.len() > 1 && pattern.starts_with('/') && pattern.ends_with('/') {
+      let re = Regex::new(&pattern[1..pattern.len() - 1])?;
+      Ok(QuestionPattern::Regex(re))
+    } else if pattern.contains('*') || pattern.contains('?') {
+      Ok(QuestionPattern::Glob(pattern.to_string()))
+    } else {
+      Ok(QuestionPattern::Exact(pattern.to_string()))
+    }
+  }
+
+  pub fn is_match(&self, name: &str) -> bool {
+    match self {
+      QuestionPattern::Exact(s) => s == name,
+      QuestionPattern::Glob(g) => glob_match(g, name),
+      QuestionPattern::Regex(re) => re.is_match(name),
+    }
+  }
+}
+
+pub fn glob_match(glob: &str, name: &str) -> bool {
+  let glob: Vec<char> = glob.chars().collect();
+  let name: Vec<char> = name.chars().collect();
+  let mut g = 0;
+  let mut n = 0;
+  // position of the last * in the glob, and the
+  // position in the name it is currently matching up to
+  let mut star: Option<(usize, usize)> = None;
+
+  while n < name.len() {
+    if g < glob.len() && (glob[g] == '?' || glob[g] == name[n]) {
+      g += 1;
+      n += 1;
+    } else if g < glob.len() && glob[g] == '*' {
+      star = Some((g, n));
+      g += 1;
+    } else if let Some((star_g, star_n)) = star {
+      // let the last * consume one more char
+      g = star_g + 1;
+      n = star_n + 1;
+      star = Some((star_g, star_n + 1));
+    } else {
+      return false;
+    }
+  }
+
+  while g < glob.len() && glob[g] == '*' {
+    g += 1;
+  }
+
+  g == glob.len()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct QuestionFilter {
+  pub include: Vec<QuestionPattern>,
+  pub exclude: Vec<QuestionPattern>,
+}
+
+impl QuestionFilter {
+  // an empty include list means every arg is included
+  pub fn is_included(&self, name: &str) -> bool {
+    let included = self.include.is_empty() ||
+      self.include.iter().any(|p| p.is_match(name));
+
+    included && !self.exclude.iter().any(|p| p.is_match(name))
+  }
+}
+
+fn parse_yaml_patterns(yaml_obj: &Yaml) -> Result<Vec<QuestionPattern>, regex::Error> {
+  let mut patterns = vec![];
+
+  if let Some(v) = yaml_obj.as_vec() {
+    for item in v {
+      if let Some(s) = get_string_from_yaml(item) {
+        patterns.push(QuestionPattern::parse(&s)?);
+      }
+    }
+  }
+
+  Ok(patterns)
+}
+
+pub fn get_question_filter_from_yaml(yaml_obj: &Yaml) -> Result<QuestionFilter, regex::Error> {
+  Ok(QuestionFilter {
+    include: parse_yaml_patterns(&yaml_obj["interactive_include"])?,
+    exclude: parse_yaml_patterns(&yaml_obj["interactive_exclude"])?,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaml_rust::YamlLoader;
+
+    #[test]
+    fn get_interactive_questions_from_yaml_works() {
+        let yaml = YamlLoader::load_from_str("
+interactive_questions:
+  - verbose:
+      default: some other default
+      background: green
+  - something else: ''
+  - port: 8080
+  - no default
+").unwrap();
+
+        let questions = get_interactive_questions_from_yaml(&yaml[0]);
+        assert_eq!(questions.len(), 4);
+        assert_eq!(questions[0].name, "verbose");
+        assert_eq!(questions[0].default, Some("some other default".to_string()));
+        assert_eq!(questions[1].name, "something else");
+        assert_eq!(questions[1].default, Some("".to_string()));
+        assert_eq!(questions[2].name, "port");
+        assert_eq!(questions[2].default, Some("8080".to_string()));
+        assert_eq!(questions[3].name, "no default");
+        assert_eq!(questions[3].default, None);
+
+        // missing section means no extra questions
+        let yaml = YamlLoader::load_from_str("name: myapp").unwrap();
+        assert!(get_interactive_questions_from_yaml(&yaml[0]).is_empty());
+    }
+
+    #[test]
+    fn glob_match_works() {
+        assert!(glob_match("verbose*", "verbose"));
+        assert!(glob_match("verbose*", "verbose10"));
+        assert!(glob_match("verbose?", "verbose2"));
+        assert!(!glob_match("verbose?", "verbose10"));
+        assert!(glob_match("*repo?", "is the current folder a git repo?"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("config", "configs"));
+    }
+
+    #[test]
+    fn get_question_filter_from_yaml_works() {
+        let yaml = YamlLoader::load_from_str("
+interactive_include:
+  - verbose*
+  - /^some/
+  - config
+interactive_exclude:
+  - config
+  - verbose1?
+").unwrap();
+
+        let filter = get_question_filter_from_yaml(&yaml[0]).unwrap();
+        assert!(filter.is_included("verbose"));
+        assert!(filter.is_included("verbose9"));
+        assert!(filter.is_included("some var with spaces?"));
+        assert!(!filter.is_included("verbose10"));
+        assert!(!filter.is_included("config"));
+        assert!(!filter.is_included("other"));
+
+        // without an include list everything not excluded is included
+        let yaml = YamlLoader::load_from_str("
+interactive_exclude:
+  - config
+").unwrap();
+        let filter = get_question_filter_from_yaml(&yaml[0]).unwrap();
+        assert!(filter.is_included("other"));
+        assert!(!filter.is_included("config"));
+
+        let yaml = YamlLoader::load_from_str("
+interactive_exclude:
+  - /(unclosed/
+").unwrap();
+        assert!(get_question_filter_from_yaml(&yaml[0]).is_err());
+    }
+
+    #[test]
+    fn get_arg_infos_from_yaml_works() {
+        let yaml = YamlLoader::load_from_str("
+interactive_questions:
+  - config:
+      help: overridden help
+  - something else: ''
+args:
+  - config:
+      short: c
+      value_name: FILE
+      help: Sets a custom config file
+      default_value: config.yml
+      env: MY_CONFIG
+  - verbose:
+      help: Sets the level of verbosity
+      long_help: Sets the level of verbosity, but longer
+  - is_git_repo:
+      possible_values:
+        - 'true'
+        - false
+  - debug:
+      short: d
+      multiple: true
+").unwrap();
+
+        let infos = get_arg_infos_from_yaml(&yaml[0]);
+        assert_eq!(infos.len(), 5);
+        let config = &infos["config"];
+        assert_eq!(config.help, Some("overridden help".to_string()));
+        assert_eq!(config.value_name, Some("FILE".to_string()));
+        assert_eq!(config.default_value, Some("config.yml".to_string()));
+        assert_eq!(config.env, Some("MY_CONFIG".to_string()));
+        let verbose = &infos["verbose"];
+        assert_eq!(verbose.long_help, Some("Sets the level of verbosity, but longer".to_string()));
+        assert_eq!(verbose.env, None);
+        assert!(verbose.possible_values.is_empty());
+        assert_eq!(infos["is_git_repo"].possible_values, vec!["true", "false"]);
+        assert!(!infos["is_git_repo"].is_flag);
+        assert!(!infos["config"].is_flag);
+        assert!(!infos["something else"].is_flag);
+        assert!(infos["verbose"].is_flag);
+        assert!(infos["debug"].is_flag);
+        assert!(infos["debug"].multiple);
+    }
+}
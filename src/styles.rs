@@ -27,7 +27,7 @@ impl TextStyle {
             use_color = self.color;
         }
 
-        let mut out_str;
+        let out_str;
 
         if let (Some(bc), Some(tc)) = (use_background, use_color) {
             // both text and background color provided
@@ -83,35 +83,81 @@ pub fn get_color_from_word(word: &str) -> Color {
 }
 
 pub fn parse_yaml_style(yaml_obj: &Yaml) -> TextStyle {
-  let mut text = "".to_string();
-  let mut color = Some(Color::Reset);
-  let mut background = Some(Color::Reset);
-  let mut highlighted_background = Some(Color::Reset);
-  let mut highlighted_color = Some(Color::Reset);
+  let reset_style = TextStyle {
+    text: "".to_string(),
+    color: Some(Color::Reset),
+    background: Some(Color::Reset),
+    highlighted_background: Some(Color::Reset),
+    highlighted_color: Some(Color::Reset),
+  };
+
+  merge_yaml_style(&reset_style, yaml_obj)
+}
+
+// same as parse_yaml_style, but the fields missing
+// from the yaml are taken from the base style.
+pub fn merge_yaml_style(base_style: &TextStyle, yaml_obj: &Yaml) -> TextStyle {
+  let mut style = base_style.clone();
 
   if let Some(t) = yaml_obj["text"].as_str() {
-    text = String::from(t);
+    style.text = String::from(t);
   }
   if let Some(c) = yaml_obj["color"].as_str() {
-    color = Some(get_color_from_word(c));
+    style.color = Some(get_color_from_word(c));
   }
   if let Some(bc) = yaml_obj["background"].as_str() {
-    background = Some(get_color_from_word(bc));
+    style.background = Some(get_color_from_word(bc));
   }
   if let Some(hb) = yaml_obj["highlighted_background"].as_str() {
-    highlighted_background = Some(get_color_from_word(hb));
+    style.highlighted_background = Some(get_color_from_word(hb));
   }
   if let Some(hc) = yaml_obj["highlighted_color"].as_str() {
-    highlighted_color = Some(get_color_from_word(hc));
+    style.highlighted_color = Some(get_color_from_word(hc));
   }
 
-  TextStyle {
-    text,
-    color,
-    background,
-    highlighted_background,
-    highlighted_color,
-  }
+  style
+}
+
+// merges the style of a single question over the global styles.
+// the question can have a style block with any of
+// prefix/question/delimiter/answer, and color keys directly on the
+// question are a shorthand for style.question:
+//
+// - verbose:
+//     background: green
+//     style:
+//       answer:
+//         color: red
+pub fn merge_styles_from_yaml(
+  style_objs: &(
+    TextStyle,
+    TextStyle,
+    TextStyle,
+    TextStyle,
+  ),
+  yaml_obj: &Yaml,
+) -> (
+    TextStyle,
+    TextStyle,
+    TextStyle,
+    TextStyle,
+) {
+  let (
+    prefix_style,
+    question_style,
+    delimiter_style,
+    answer_style,
+  ) = style_objs;
+
+  let style_yaml = &yaml_obj["style"];
+  let question_style = merge_yaml_style(question_style, yaml_obj);
+
+  (
+    merge_yaml_style(prefix_style, &style_yaml["prefix"]),
+    merge_yaml_style(&question_style, &style_yaml["question"]),
+    merge_yaml_style(delimiter_style, &style_yaml["delimiter"]),
+    merge_yaml_style(answer_style, &style_yaml["answer"]),
+  )
 }
 
 pub fn get_styles_from_yaml(yaml_obj: &Yaml) -> (
@@ -152,24 +198,21 @@ pub fn get_styles_from_yaml(yaml_obj: &Yaml) -> (
     highlighted_color: None,
   };
 
-  match yaml_obj["interactive_style"].as_hash() {
-    Some(v) => {
-      for (key, value) in v {
-        if let Some(key_str) = key.as_str() {
-          if key_str == "prefix" {
-            prefix_style = parse_yaml_style(value);
-          } else if key_str == "delimiter" {
-            delimiter_style = parse_yaml_style(value);
-          } else if key_str == "question" {
-            question_style = parse_yaml_style(value);
-          } else if key_str == "answer" {
-            answer_style = parse_yaml_style(value);
-          }
+  if let Some(v) = yaml_obj["interactive_style"].as_hash() {
+    for (key, value) in v {
+      if let Some(key_str) = key.as_str() {
+        if key_str == "prefix" {
+          prefix_style = parse_yaml_style(value);
+        } else if key_str == "delimiter" {
+          delimiter_style = parse_yaml_style(value);
+        } else if key_str == "question" {
+          question_style = parse_yaml_style(value);
+        } else if key_str == "answer" {
+          answer_style = parse_yaml_style(value);
         }
       }
-    },
-    None => (),
-  };
+    }
+  }
 
   (
     prefix_style,
@@ -184,6 +227,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn get_console_string_works() {
         let test_string = "12345";
         let mut my_text_style = TextStyle {
@@ -238,4 +282,42 @@ mod tests {
             true,
         );
     }
+
+    #[test]
+    fn merge_styles_from_yaml_works() {
+        let yaml = yaml_rust::YamlLoader::load_from_str("
+interactive_style:
+  prefix:
+    text: '> '
+  question:
+    highlighted_color: red
+verbose:
+  background: green
+  style:
+    answer:
+      color: red
+    prefix:
+      text: '! '
+").unwrap();
+
+        let global_styles = get_styles_from_yaml(&yaml[0]);
+        let (
+            prefix_style,
+            question_style,
+            delimiter_style,
+            answer_style,
+        ) = merge_styles_from_yaml(&global_styles, &yaml[0]["verbose"]);
+
+        assert_eq!(prefix_style.text, "! ");
+        // shorthand keys merge over the global question style
+        assert_eq!(question_style.background, Some(Color::Green));
+        assert_eq!(question_style.highlighted_color, Some(Color::Red));
+        assert_eq!(delimiter_style.text, ": ");
+        assert_eq!(answer_style.color, Some(Color::Red));
+        assert_eq!(answer_style.background, None);
+
+        // questions without styles keep the global styles
+        let (_, question_style, _, _) = merge_styles_from_yaml(&global_styles, &yaml_rust::Yaml::Null);
+        assert_eq!(question_style.background, global_styles.1.background);
+    }
 }
@@ -1,6 +1,12 @@
-use clap::{ArgMatches};
+use clap::ArgMatches;
+use crossterm::Color;
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-use super::styles::TextStyle;
+use super::styles::{TextStyle, merge_styles_from_yaml};
+use super::questions::{ArgInfo, InteractiveQuestion, QuestionFilter};
+use super::validation::Validator;
 
 
 #[derive(Debug, Clone)]
@@ -13,9 +19,25 @@ pub struct ListItem {
   pub question_style: TextStyle,
   pub delimiter_style: TextStyle,
   pub answer_style: TextStyle,
+  // false for questions that come from interactive_questions
+  // and do not exist in the clap args.
+  pub is_arg: bool,
+  // help text and other details shown in the help pane
+  pub info: ArgInfo,
+  // all of the values of an arg that takes multiple values,
+  // and the one that is currently being edited.
+  // the answer is not used for these args.
+  pub values: Vec<String>,
+  pub value_index: usize,
+  // the number of grapheme clusters of the edit text before the cursor
+  pub edit_cursor: usize,
+  pub validators: Vec<Validator>,
+  // the error of the last validate(), shown next to the answer
+  pub error: Option<String>,
 }
 
 impl ListItem {
+  #[allow(clippy::too_many_arguments)]
   pub fn new(
     s: &str,
     s2: &str,
@@ -35,9 +57,275 @@ impl ListItem {
       question_style: qs,
       delimiter_style: ds,
       answer_style: ans,
+      is_arg: true,
+      info: ArgInfo::default(),
+      values: vec![],
+      value_index: 0,
+      edit_cursor: 0,
+      validators: vec![],
+      error: None,
     }
   }
 
+  pub fn from_styles(
+    question: &str,
+    answer: &str,
+    style_objs: &(
+        TextStyle,
+        TextStyle,
+        TextStyle,
+        TextStyle,
+    ),
+  ) -> ListItem {
+    let (
+        prefix_style,
+        question_style,
+        delimiter_style,
+        answer_style,
+    ) = style_objs;
+
+    let mut item = ListItem::new(
+      "",
+      "",
+      question,
+      answer,
+      prefix_style.clone(),
+      question_style.clone(),
+      delimiter_style.clone(),
+      answer_style.clone(),
+    );
+    item.update_output();
+    item
+  }
+
+  pub fn set_styles(
+    &mut self,
+    style_objs: &(
+        TextStyle,
+        TextStyle,
+        TextStyle,
+        TextStyle,
+    ),
+  ) {
+    let (
+        prefix_style,
+        question_style,
+        delimiter_style,
+        answer_style,
+    ) = style_objs;
+
+    self.prefix_style = prefix_style.clone();
+    self.question_style = question_style.clone();
+    self.delimiter_style = delimiter_style.clone();
+    self.answer_style = answer_style.clone();
+    self.update_output();
+  }
+
+  // flags store "true"/"false", or the number
+  // of occurrences for multiple flags like -vvv
+  pub fn get_flag_count(&self) -> u64 {
+    match self.answer.as_str() {
+      "true" => 1,
+      "false" => 0,
+      a => a.parse().unwrap_or(0),
+    }
+  }
+
+  pub fn set_flag_count(&mut self, count: u64) {
+    if self.info.multiple {
+      self.answer = count.to_string();
+    } else {
+      self.answer = (count > 0).to_string();
+    }
+    self.update_output();
+  }
+
+  // checks/unchecks a flag, or adds an
+  // occurrence if the flag is multiple
+  pub fn toggle_flag(&mut self) {
+    let count = self.get_flag_count();
+    if self.info.multiple {
+      self.set_flag_count(count + 1);
+    } else {
+      self.set_flag_count(if count > 0 { 0 } else { 1 });
+    }
+  }
+
+  pub fn is_multiple(&self) -> bool {
+    (self.info.multiple && !self.info.is_flag) || self.values.len() > 1
+  }
+
+  // the text that is changed in edit mode: the answer, or
+  // the selected value of an arg that takes multiple values
+  pub fn get_edit_text(&self) -> &str {
+    if self.is_multiple() {
+      match self.values.get(self.value_index) {
+        Some(v) => v,
+        None => "",
+      }
+    } else {
+      &self.answer
+    }
+  }
+
+  pub fn get_edit_text_mut(&mut self) -> &mut String {
+    if !self.is_multiple() {
+      return &mut self.answer;
+    }
+
+    if self.values.is_empty() {
+      self.values.push("".to_string());
+    }
+    if self.value_index >= self.values.len() {
+      self.value_index = self.values.len() - 1;
+    }
+    &mut self.values[self.value_index]
+  }
+
+  // sets the answer, or the only value of an
+  // arg that takes multiple values
+  pub fn set_answer(&mut self, answer: &str) {
+    if self.is_multiple() {
+      self.values = vec![answer.to_string()];
+      self.value_index = 0;
+    } else {
+      self.answer = answer.to_string();
+    }
+    self.move_edit_cursor_to_end();
+    self.validate();
+    self.update_output();
+  }
+
+  pub fn move_edit_cursor_to_end(&mut self) {
+    self.edit_cursor = self.get_edit_text().graphemes(true).count();
+  }
+
+  // the column of the edit cursor in the highlighted output.
+  // wide chars like CJK take two columns.
+  pub fn get_edit_column(&self) -> usize {
+    let mut column = self.prefix_style.text.width()
+      + self.question.width()
+      + self.delimiter_style.text.width();
+
+    if self.is_multiple() {
+      // the values before the edited one, and their ", "
+      column += self.values.iter()
+        .take(self.value_index)
+        .map(|v| v.width() + 2)
+        .sum::<usize>();
+    }
+
+    let before_cursor: String = self.get_edit_text()
+      .graphemes(true)
+      .take(self.edit_cursor)
+      .collect();
+    column + before_cursor.width()
+  }
+
+  // the answer as it is shown in the list,
+  // flags are shown as checkboxes
+  pub fn get_display_answer(&self) -> String {
+    if self.is_multiple() {
+      return self.values.join(", ");
+    }
+    if !self.info.is_flag {
+      return self.answer.clone();
+    }
+
+    let count = self.get_flag_count();
+    if self.info.multiple {
+      format!("[{}]", count)
+    } else if count > 0 {
+      "[x]".to_string()
+    } else {
+      "[ ]".to_string()
+    }
+  }
+
+  // runs the validators on the answer (or on every value of
+  // an arg that takes multiple values), and keeps the first error.
+  // answers of args with possible values must be one of them.
+  pub fn validate(&mut self) -> bool {
+    self.error = None;
+    if self.info.is_flag {
+      return true;
+    }
+
+    let answers = if self.is_multiple() {
+      self.values.clone()
+    } else {
+      vec![self.answer.clone()]
+    };
+
+    for answer in answers.iter() {
+      let possible_values = &self.info.possible_values;
+      if !possible_values.is_empty() && !possible_values.contains(answer) {
+        self.error = Some(format!("must be one of: {}", possible_values.join(", ")));
+        return false;
+      }
+      for validator in self.validators.iter() {
+        if let Err(e) = validator.validate(answer) {
+          self.error = Some(e);
+          return false;
+        }
+      }
+    }
+
+    true
+  }
+
+  // rebuilds the regular and highlighted outputs
+  // after the question, answer, or styles change.
+  pub fn update_output(&mut self) {
+    let answer = self.get_display_answer();
+    let error = match &self.error {
+      Some(e) => TextStyle {
+        text: "".to_string(),
+        color: Some(Color::Red),
+        background: None,
+        highlighted_color: Some(Color::Red),
+        highlighted_background: None,
+      }.get_console_string(&format!("  <- {}", e), false),
+      None => "".to_string(),
+    };
+
+    self.regular_output = format!(
+      "{}{}{}{}",
+      self.question_style.get_console_string(&self.question, false),
+      self.delimiter_style.get_console_string(&self.delimiter_style.text, false),
+      self.answer_style.get_console_string(&answer, false),
+      error,
+    );
+
+    self.highlighted_output = format!(
+      "{}{}{}{}{}",
+      self.prefix_style.get_console_string(&self.prefix_style.text, true),
+      self.question_style.get_console_string(&self.question, true),
+      self.delimiter_style.get_console_string(&self.delimiter_style.text, true),
+      self.answer_style.get_console_string(&answer, true),
+      error,
+    );
+  }
+
+  // the same text as the output, without the colors.
+  // this is what the layout is measured on.
+  pub fn get_visible_text(&self, is_highlighted: bool) -> String {
+    let prefix = if is_highlighted { self.prefix_style.text.as_str() } else { "" };
+    let error = match &self.error {
+      Some(e) => format!("  <- {}", e),
+      None => "".to_string(),
+    };
+
+    format!(
+      "{}{}{}{}{}",
+      prefix,
+      self.question,
+      self.delimiter_style.text,
+      self.get_display_answer(),
+      error,
+    )
+  }
+
   pub fn get_output(&self, is_highlighted: bool) -> &str {
     if is_highlighted {
       &self.highlighted_output
@@ -47,6 +335,20 @@ impl ListItem {
   }
 }
 
+// the number of columns the text takes in the terminal
+pub fn get_text_width(text: &str) -> usize {
+  text.width()
+}
+
+// removes the last grapheme cluster, like an emoji
+// or a letter with its accents, instead of the last char
+pub fn pop_grapheme(text: &mut String) -> Option<String> {
+  let start = text.grapheme_indices(true).next_back()?.0;
+  let last = text[start..].to_string();
+  text.truncate(start);
+  Some(last)
+}
+
 pub fn get_list_items_from_matches(
     matches: &ArgMatches,
     style_objs: &(
@@ -57,16 +359,9 @@ pub fn get_list_items_from_matches(
     ),
 ) -> Vec<ListItem> {
 
-    let (
-        prefix_style,
-        question_style,
-        delimiter_style,
-        answer_style,
-    ) = style_objs;
-
     let mut my_vec: Vec<ListItem> = vec![];
     for item in matches.args.iter() {
-        if item.1.vals.len() < 1 {
+        if item.1.vals.is_empty() {
           // skip args that do not contain values,
           // eg: true/false flags
           continue;
@@ -76,49 +371,173 @@ pub fn get_list_items_from_matches(
         let default = item.1.vals[0].clone();
         let default_answer = &default.into_string().unwrap();
 
-        let my_str = format!(
-            "{}{}{}",
-            question_style.get_console_string(question, false),
-            delimiter_style.get_console_string(&delimiter_style.text, false),
-            answer_style.get_console_string(default_answer, false),
-        );
-
-        let my_str_highlighted = format!(
-            "{}{}{}{}",
-            prefix_style.get_console_string(&prefix_style.text, true),
-            question_style.get_console_string(question, true),
-            delimiter_style.get_console_string(&delimiter_style.text, true),
-            answer_style.get_console_string(default_answer, true),
-        );
-
-        let my_question = ListItem::new(
-            &my_str,
-            &my_str_highlighted,
-            question,
-            default_answer,
-            prefix_style.clone(),
-            question_style.clone(),
-            delimiter_style.clone(),
-            answer_style.clone(),
-        );
-        my_vec.push(my_question);
+        let mut list_item = ListItem::from_styles(question, default_answer, style_objs);
+        if item.1.vals.len() > 1 {
+            // stays an arg with multiple values when some are removed
+            list_item.info.multiple = true;
+            list_item.values = item.1.vals.iter()
+                .map(|v| v.to_string_lossy().to_string())
+                .collect();
+            list_item.update_output();
+        }
+        my_vec.push(list_item);
     }
 
 
     my_vec
 }
 
-pub fn replace_matches_from_list_items<'a, 'b>(
-    matches: &'a ArgMatches<'b>,
-    list: &'b Vec<ListItem>,
+// adds the interactive_questions that are not clap args to the list,
+// and replaces the answers of args that were not provided by the user
+// (ie: clap's default value) with the interactive default.
+// the style of each question is merged over the global styles.
+pub fn apply_interactive_questions(
+    matches: &ArgMatches,
+    list: &mut Vec<ListItem>,
+    questions: &[InteractiveQuestion],
+    style_objs: &(
+        TextStyle,
+        TextStyle,
+        TextStyle,
+        TextStyle,
+    ),
+) {
+    for question in questions.iter() {
+        let question_styles = merge_styles_from_yaml(style_objs, &question.yaml);
+
+        let existing = list.iter_mut().find(|item| item.question == question.name);
+        if let Some(item) = existing {
+            let user_provided = match matches.args.get::<str>(&question.name) {
+                Some(match_item) => match_item.occurs > 0,
+                None => false,
+            };
+            if let (false, Some(default)) = (user_provided, &question.default) {
+                item.answer = default.clone();
+            }
+            item.set_styles(&question_styles);
+            continue;
+        }
+
+        let default = question.default.clone().unwrap_or_default();
+        let mut item = ListItem::from_styles(&question.name, &default, &question_styles);
+        item.is_arg = false;
+        list.push(item);
+    }
+}
+
+pub fn apply_arg_infos(
+    list: &mut [ListItem],
+    infos: &HashMap<String, ArgInfo>,
+) {
+    for item in list.iter_mut() {
+        if let Some(info) = infos.get(&item.question) {
+            let multiple = item.info.multiple;
+            item.info = info.clone();
+            item.info.multiple |= multiple;
+            if item.is_multiple() && item.values.is_empty() && !item.answer.is_empty() {
+                // the arg takes multiple values, but only one was given
+                item.values.push(item.answer.clone());
+            }
+            item.update_output();
+        }
+    }
+}
+
+// adds the validators to the list items with the same name,
+// and validates every item so existing errors are shown
+pub fn apply_validators(
+    list: &mut [ListItem],
+    validators: &HashMap<String, Vec<Validator>>,
+) {
+    for item in list.iter_mut() {
+        if let Some(item_validators) = validators.get(&item.question) {
+            item.validators.extend(item_validators.iter().cloned());
+        }
+        item.validate();
+        item.update_output();
+    }
+}
+
+pub fn all_list_items_valid(list: &[ListItem]) -> bool {
+    list.iter().all(|item| item.error.is_none())
+}
+
+// flags do not have values, so they are skipped by
+// get_list_items_from_matches, and flags that were not
+// provided are not in the matches at all. this adds a
+// list item for every flag in the arg infos.
+pub fn add_flag_list_items(
+    matches: &ArgMatches,
+    list: &mut Vec<ListItem>,
+    infos: &HashMap<String, ArgInfo>,
+    style_objs: &(
+        TextStyle,
+        TextStyle,
+        TextStyle,
+        TextStyle,
+    ),
+) {
+    let mut flag_names: Vec<&String> = infos.iter()
+        .filter(|(_, info)| info.is_flag)
+        .map(|(name, _)| name)
+        .collect();
+    flag_names.sort();
+
+    for name in flag_names {
+        if list.iter().any(|item| item.question == *name) {
+            continue;
+        }
+
+        let mut item = ListItem::from_styles(name, "", style_objs);
+        item.info = infos[name].clone();
+        item.set_flag_count(matches.occurrences_of(name));
+        list.push(item);
+    }
+}
+
+// removes the args that are not allowed by interactive_include or are
+// denied by interactive_exclude. the removed args keep their
+// user provided or default value in the matches.
+// questions from interactive_questions are always kept.
+pub fn filter_list_items(
+    list: &mut Vec<ListItem>,
+    filter: &QuestionFilter,
+) {
+    list.retain(|item| !item.is_arg || filter.is_included(&item.question));
+}
+
+pub fn replace_matches_from_list_items<'b>(
+    matches: &ArgMatches<'b>,
+    list: &'b [ListItem],
 ) -> ArgMatches<'b> {
     let mut cloned_args = matches.args.clone();
 
     for listitem in list.iter() {
+        if listitem.info.is_flag {
+            // flags are present in the matches only if they occur
+            let count = listitem.get_flag_count();
+            let existing = cloned_args.remove::<str>(&listitem.question);
+            if count > 0 {
+                let mut item_clone = existing.unwrap_or_default();
+                item_clone.occurs = count;
+                cloned_args.insert(&listitem.question, item_clone);
+            }
+            continue;
+        }
+
         if let Some(match_item) = cloned_args.get::<str>(&listitem.question) {
             let mut item_clone = match_item.clone();
             cloned_args.remove::<str>(&listitem.question);
-            item_clone.vals[0] = std::ffi::OsString::from(&listitem.answer);
+            if listitem.is_multiple() && listitem.values.is_empty() {
+                // every value was removed, so the arg is not present
+                continue;
+            } else if listitem.is_multiple() {
+                item_clone.vals = listitem.values.iter()
+                    .map(std::ffi::OsString::from)
+                    .collect();
+            } else {
+                item_clone.vals[0] = std::ffi::OsString::from(&listitem.answer);
+            }
             cloned_args.insert(&listitem.question, item_clone);
         }
     }
@@ -128,4 +547,161 @@ pub fn replace_matches_from_list_items<'a, 'b>(
         subcommand: matches.subcommand.clone(),
         usage:  matches.usage.clone(),
     }
-}
\ No newline at end of file
+}
+
+// same as replace_matches_from_list_items, but also returns
+// the answers to the interactive_questions that are not clap args
+pub fn replace_matches_and_get_answers<'b>(
+    matches: &ArgMatches<'b>,
+    list: &'b [ListItem],
+) -> (ArgMatches<'b>, HashMap<String, String>) {
+    let mut answers = HashMap::new();
+    for listitem in list.iter() {
+        if !listitem.is_arg {
+            answers.insert(listitem.question.clone(), listitem.answer.clone());
+        }
+    }
+
+    (replace_matches_from_list_items(matches, list), answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{App, Arg};
+    use crate::questions::InteractiveQuestion;
+    use crate::styles::get_styles_from_yaml;
+    use yaml_rust::Yaml;
+
+    #[test]
+    fn apply_interactive_questions_works() {
+        let matches = App::new("myapp")
+            .arg(Arg::with_name("a").short("a").takes_value(true).default_value("clap"))
+            .arg(Arg::with_name("b").short("b").takes_value(true).default_value("clap"))
+            .get_matches_from(vec!["myapp", "-b", "user"]);
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut list = get_list_items_from_matches(&matches, &styles);
+
+        let questions = vec![
+            InteractiveQuestion { name: "a".to_string(), default: Some("interactive".to_string()), yaml: Yaml::Null },
+            InteractiveQuestion { name: "b".to_string(), default: Some("interactive".to_string()), yaml: Yaml::Null },
+            InteractiveQuestion { name: "c".to_string(), default: Some("extra".to_string()), yaml: Yaml::Null },
+        ];
+        apply_interactive_questions(&matches, &mut list, &questions, &styles);
+        assert_eq!(list.len(), 3);
+
+        // clap defaults are replaced, user provided values are not
+        let (new_matches, answers) = replace_matches_and_get_answers(&matches, &list);
+        assert_eq!(new_matches.value_of("a"), Some("interactive"));
+        assert_eq!(new_matches.value_of("b"), Some("user"));
+        assert_eq!(new_matches.value_of("c"), None);
+        assert_eq!(answers.len(), 1);
+        assert_eq!(answers["c"], "extra");
+    }
+
+    #[test]
+    fn flag_list_items_work() {
+        let yaml = yaml_rust::YamlLoader::load_from_str("
+name: myapp
+args:
+  - debug:
+      short: d
+  - verbose:
+      short: v
+      multiple: true
+  - quiet:
+      short: q
+").unwrap();
+        let app = App::from_yaml(&yaml[0]);
+        let matches = app.get_matches_from(vec!["myapp", "-vv", "-q"]);
+        let infos = crate::questions::get_arg_infos_from_yaml(&yaml[0]);
+        let styles = get_styles_from_yaml(&Yaml::Null);
+
+        let mut list = get_list_items_from_matches(&matches, &styles);
+        assert!(list.is_empty());
+        add_flag_list_items(&matches, &mut list, &infos, &styles);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0].question, "debug");
+        assert_eq!(list[0].get_display_answer(), "[ ]");
+        assert_eq!(list[1].get_display_answer(), "[x]");
+        assert_eq!(list[2].get_display_answer(), "[2]");
+
+        list[0].toggle_flag();
+        list[1].toggle_flag();
+        list[2].toggle_flag();
+        let new_matches = replace_matches_from_list_items(&matches, &list);
+        assert!(new_matches.is_present("debug"));
+        assert!(!new_matches.is_present("quiet"));
+        assert_eq!(new_matches.occurrences_of("verbose"), 3);
+    }
+
+    #[test]
+    fn multiple_values_work() {
+        let yaml = yaml_rust::YamlLoader::load_from_str("
+name: myapp
+args:
+  - include:
+      short: I
+      takes_value: true
+      multiple: true
+      number_of_values: 1
+  - exclude:
+      short: E
+      takes_value: true
+      multiple: true
+      number_of_values: 1
+").unwrap();
+        let app = App::from_yaml(&yaml[0]);
+        let matches = app.get_matches_from(vec!["myapp", "-I", "a", "-I", "b", "-E", "c"]);
+        let infos = crate::questions::get_arg_infos_from_yaml(&yaml[0]);
+        let styles = get_styles_from_yaml(&Yaml::Null);
+
+        let mut list = get_list_items_from_matches(&matches, &styles);
+        list.sort_by(|a, b| a.question.cmp(&b.question));
+        apply_arg_infos(&mut list, &infos);
+        assert_eq!(list[0].question, "exclude");
+        assert_eq!(list[0].values, vec!["c"]);
+        assert_eq!(list[1].values, vec!["a", "b"]);
+        assert_eq!(list[1].get_display_answer(), "a, b");
+
+        list[0].value_index = 1;
+        list[0].get_edit_text_mut().push('d');
+        assert_eq!(list[0].values, vec!["cd"]);
+        list[1].values.push("z".to_string());
+
+        let new_matches = replace_matches_from_list_items(&matches, &list);
+        let excludes: Vec<&str> = new_matches.values_of("exclude").unwrap().collect();
+        let includes: Vec<&str> = new_matches.values_of("include").unwrap().collect();
+        assert_eq!(excludes, vec!["cd"]);
+        assert_eq!(includes, vec!["a", "b", "z"]);
+
+        // without an arg info, args with many values stay multiple
+        let mut list = get_list_items_from_matches(&matches, &styles);
+        list.sort_by(|a, b| a.question.cmp(&b.question));
+        let mut infos = infos.clone();
+        infos.remove("include");
+        apply_arg_infos(&mut list, &infos);
+        list[1].values.remove(0);
+        assert!(list[1].is_multiple());
+        list[0].values.clear();
+        let new_matches = replace_matches_from_list_items(&matches, &list);
+        let includes: Vec<&str> = new_matches.values_of("include").unwrap().collect();
+        assert_eq!(includes, vec!["b"]);
+        assert!(!new_matches.is_present("exclude"));
+    }
+
+    #[test]
+    fn unicode_width_works() {
+        let mut text = "a\u{4E16}e\u{301}".to_string();
+        assert_eq!(get_text_width(&text), 4);
+        assert_eq!(pop_grapheme(&mut text), Some("e\u{301}".to_string()));
+        assert_eq!(text, "a\u{4E16}");
+
+        let styles = get_styles_from_yaml(&Yaml::Null);
+        let mut item = ListItem::from_styles("\u{540D}", "\u{4E16}x", &styles);
+        item.move_edit_cursor_to_end();
+        assert_eq!(item.edit_cursor, 2);
+        let question_column = get_text_width(&styles.0.text) + 2 + get_text_width(&styles.2.text);
+        assert_eq!(item.get_edit_column(), question_column + 3);
+    }
+}
@@ -0,0 +1,204 @@
+use clap::App;
+use regex::Regex;
+use yaml_rust::{Yaml};
+use std::{collections::HashMap, fmt, path::Path, rc::Rc};
+
+use super::questions::get_string_from_yaml;
+
+#[derive(Clone)]
+pub enum Validator {
+  Integer,
+  Float,
+  Regex(Regex),
+  ExistingPath,
+  // same signature as clap's Arg::validator
+  Custom(Rc<dyn Fn(String) -> Result<(), String>>),
+}
+
+impl fmt::Debug for Validator {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Validator::Integer => write!(f, "Integer"),
+      Validator::Float => write!(f, "Float"),
+      Validator::Regex(re) => write!(f, "Regex({})", re.as_str()),
+      Validator::ExistingPath => write!(f, "ExistingPath"),
+      Validator::Custom(_) => write!(f, "Custom"),
+    }
+  }
+}
+
+impl Validator {
+  pub fn validate(&self, value: &str) -> Result<(), String> {
+    match self {
+      Validator::Integer => match value.parse::<i64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err("must be an integer".to_string()),
+      },
+      Validator::Float => match value.parse::<f64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err("must be a number".to_string()),
+      },
+      Validator::Regex(re) => {
+        if re.is_match(value) {
+          Ok(())
+        } else {
+          Err(format!("must match {}", re.as_str()))
+        }
+      },
+      Validator::ExistingPath => {
+        if Path::new(value).exists() {
+          Ok(())
+        } else {
+          Err("path does not exist".to_string())
+        }
+      },
+      Validator::Custom(f) => f(value.to_string()),
+    }
+  }
+}
+
+// validate: integer
+// validate: [float, existing_path, { regex: '^[a-z]+$' }]
+// an unknown validator is an error, so a typo
+// does not leave the question without validation
+pub fn parse_yaml_validators(yaml_obj: &Yaml) -> Result<Vec<Validator>, String> {
+  let mut validators = vec![];
+
+  let items = match yaml_obj {
+    Yaml::BadValue | Yaml::Null => vec![],
+    Yaml::Array(v) => v.clone(),
+    _ => vec![yaml_obj.clone()],
+  };
+
+  for item in items.iter() {
+    if let Some(re) = get_string_from_yaml(&item["regex"]) {
+      let re = Regex::new(&re).map_err(|e| format!("invalid regex: {}", e))?;
+      validators.push(Validator::Regex(re));
+    } else if let Some(kind) = item.as_str() {
+      match kind {
+        "integer" => validators.push(Validator::Integer),
+        "float" => validators.push(Validator::Float),
+        "existing_path" => validators.push(Validator::ExistingPath),
+        _ => return Err(format!("unknown validator \"{}\"", kind)),
+      }
+    } else {
+      return Err(format!("invalid validator {:?}", item));
+    }
+  }
+
+  Ok(validators)
+}
+
+// clap panics on unknown keys in the args, so the validators
+// are declared on the interactive_questions:
+//
+// interactive_questions:
+//   - port:
+//       validate: integer
+pub fn get_validators_from_yaml(yaml_obj: &Yaml) -> Result<HashMap<String, Vec<Validator>>, String> {
+  let mut validators = HashMap::new();
+
+  if let Some(v) = yaml_obj["interactive_questions"].as_vec() {
+    for item in v {
+      if let Some(h) = item.as_hash() {
+        for (key, value) in h {
+          if let Some(key_str) = get_string_from_yaml(key) {
+            let question_validators = parse_yaml_validators(&value["validate"])
+              .map_err(|e| format!("{}: {}", key_str, e))?;
+            if !question_validators.is_empty() {
+              validators.insert(key_str, question_validators);
+            }
+          }
+        }
+      }
+    }
+  }
+
+  Ok(validators)
+}
+
+// the validators that were set on the clap args with Arg::validator
+pub fn get_validators_from_app(app: &App) -> HashMap<String, Vec<Validator>> {
+  let mut validators = HashMap::new();
+
+  for opt in app.p.opts.iter() {
+    if let Some(f) = &opt.v.validator {
+      validators.insert(opt.b.name.to_string(), vec![Validator::Custom(f.clone())]);
+    }
+  }
+  for (_, pos) in app.p.positionals.iter() {
+    if let Some(f) = &pos.v.validator {
+      validators.insert(pos.b.name.to_string(), vec![Validator::Custom(f.clone())]);
+    }
+  }
+
+  validators
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+    use yaml_rust::YamlLoader;
+
+    #[test]
+    fn get_validators_from_yaml_works() {
+        let yaml = YamlLoader::load_from_str("
+interactive_questions:
+  - port:
+      validate: integer
+  - name:
+      validate:
+        - regex: '^[a-z]+$'
+  - ratio:
+      validate: [float]
+  - no validation: ''
+").unwrap();
+
+        let validators = get_validators_from_yaml(&yaml[0]).unwrap();
+        assert_eq!(validators.len(), 3);
+        assert!(validators["port"][0].validate("8080").is_ok());
+        assert!(validators["port"][0].validate("80.5").is_err());
+        assert!(validators["ratio"][0].validate("80.5").is_ok());
+        assert!(validators["name"][0].validate("abc").is_ok());
+        assert_eq!(
+            validators["name"][0].validate("ABC"),
+            Err("must match ^[a-z]+$".to_string()),
+        );
+        assert!(Validator::ExistingPath.validate(".").is_ok());
+    }
+
+    #[test]
+    fn get_validators_from_yaml_fails_on_unknown_validators() {
+        let get_error = |text: &str| {
+            let yaml = YamlLoader::load_from_str(text).unwrap();
+            get_validators_from_yaml(&yaml[0]).unwrap_err()
+        };
+
+        assert_eq!(
+            get_error("interactive_questions: [port: { validate: interger }]"),
+            "port: unknown validator \"interger\"",
+        );
+        assert_eq!(
+            get_error("interactive_questions: [port: { validate: [integer, 5] }]"),
+            "port: invalid validator Integer(5)",
+        );
+        assert!(get_error("interactive_questions: [name: { validate: { regex: '(' } }]")
+            .starts_with("name: invalid regex: "));
+    }
+
+    #[test]
+    fn get_validators_from_app_works() {
+        let app = App::new("myapp")
+            .arg(Arg::with_name("port").long("port").takes_value(true)
+                .validator(|v| if v == "0" { Err("not 0".to_string()) } else { Ok(()) }))
+            .arg(Arg::with_name("file").index(1)
+                .validator(|_| Ok(())))
+            .arg(Arg::with_name("other").long("other").takes_value(true));
+
+        let validators = get_validators_from_app(&app);
+        assert_eq!(validators.len(), 2);
+        assert_eq!(validators["port"][0].validate("0"), Err("not 0".to_string()));
+        assert!(validators["port"][0].validate("1").is_ok());
+    }
+}